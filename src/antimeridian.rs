@@ -0,0 +1,253 @@
+use clap::ValueEnum;
+use geo::{BooleanOps, BoundingRect, MapCoords, Translate, unary_union};
+use geo_types::{
+    Coord, Geometry, LineString, MultiLineString, MultiPolygon, Polygon, Rect,
+};
+use h3o::CellIndex;
+
+/// Distance to the antimeridian, in degrees, under which longitudes are snapped
+/// onto it (about 1cm at the equator).
+const SNAP_TOLERANCE: f64 = 1e-7;
+
+/// How geometries crossing the antimeridian are handled.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum Mode {
+    /// Cut geometries along the antimeridian, as per RFC 7946.
+    Split,
+    /// Shift longitudes past ±180° to keep geometries contiguous.
+    Unwrap,
+}
+
+/// Fix a linestring crossing the antimeridian.
+///
+/// Returns a `MultiLineString` when the linestring is split, or the
+/// linestring itself otherwise. Unwrapped linestrings are centered on the
+/// [-180°, 180°] range, whatever their starting point.
+pub fn line(line: LineString, mode: Mode) -> Geometry {
+    if !crosses(&line) {
+        return line.into();
+    }
+    let coords = unwrap(&line.0, None);
+
+    match mode {
+        Mode::Unwrap => {
+            let line = LineString(coords);
+            let offset = wrap_offset(&line);
+            line.translate(offset, 0.).into()
+        }
+        Mode::Split => MultiLineString(split_line(&coords)).into(),
+    }
+}
+
+/// Fix a polygon crossing the antimeridian.
+///
+/// Returns a `MultiPolygon` when the polygon is split, or the polygon itself
+/// otherwise. Unwrapped polygons are centered on the [-180°, 180°] range,
+/// whatever their starting point.
+pub fn polygon(polygon: Polygon, mode: Mode) -> Geometry {
+    if !crosses(polygon.exterior()) && !polygon.interiors().iter().any(crosses)
+    {
+        return polygon.into();
+    }
+    let (unwrapped, is_polar) = unwrap_polygon(&polygon);
+
+    match mode {
+        Mode::Unwrap => {
+            let offset = wrap_offset(unwrapped.exterior());
+            unwrapped.translate(offset, 0.).into()
+        }
+        // Around a pole, parts are also split where the ring was closed.
+        Mode::Split if is_polar => {
+            clamp(&unary_union(&split_polygon(&unwrapped))).into()
+        }
+        Mode::Split => split_polygon(&unwrapped).into(),
+    }
+}
+
+/// Fix every polygon of a multipolygon crossing the antimeridian.
+pub fn multi_polygon(polygons: MultiPolygon, mode: Mode) -> MultiPolygon {
+    polygons
+        .into_iter()
+        .flat_map(|polygon| match self::polygon(polygon, mode) {
+            Geometry::MultiPolygon(polygons) => polygons.0,
+            Geometry::Polygon(polygon) => vec![polygon],
+            _ => unreachable!("polygon fix returns (multi)polygons"),
+        })
+        .collect()
+}
+
+/// Returns the boundary of a cell, split along the antimeridian.
+pub fn cell(index: CellIndex) -> MultiPolygon {
+    let ring: LineString = index.boundary().into();
+    multi_polygon(
+        MultiPolygon::new(vec![Polygon::new(ring, Vec::new())]),
+        Mode::Split,
+    )
+}
+
+/// Returns true if one of the line segments crosses the antimeridian.
+///
+/// Segments spanning more than 180° of longitude are assumed to take the
+/// shortest path, through the antimeridian.
+fn crosses(line: &LineString) -> bool {
+    line.lines()
+        .any(|segment| (segment.end.x - segment.start.x).abs() > 180.)
+}
+
+/// Shift the longitudes so that consecutive coordinates are less than 180°
+/// apart.
+///
+/// The first coordinate is shifted to be less than 180° away from `anchor`, if
+/// any, or left as is.
+fn unwrap(coords: &[Coord], anchor: Option<f64>) -> Vec<Coord> {
+    let mut previous = anchor;
+    coords
+        .iter()
+        .map(|&coord| {
+            let x = previous.map_or(coord.x, |previous| {
+                360_f64.mul_add(((previous - coord.x) / 360.).round(), coord.x)
+            });
+            previous = Some(x);
+            Coord { x, ..coord }
+        })
+        .collect()
+}
+
+/// Unwrap the rings of a polygon.
+///
+/// A ring around a pole doesn't close once unwrapped: it's closed by going
+/// through the pole, and the polygon is flagged as polar.
+fn unwrap_polygon(polygon: &Polygon) -> (Polygon, bool) {
+    let mut exterior = unwrap(&polygon.exterior().0, None);
+    let anchor = exterior.first().map(|coord| coord.x);
+    let is_polar = if let (Some(&first), Some(&last)) =
+        (exterior.first(), exterior.last())
+        && (last.x - first.x).abs() > 180.
+    {
+        let pole = if exterior.iter().map(|coord| coord.y).sum::<f64>() > 0. {
+            90.
+        } else {
+            -90.
+        };
+        exterior.extend([
+            Coord { x: last.x, y: pole },
+            Coord {
+                x: first.x,
+                y: pole,
+            },
+            first,
+        ]);
+        true
+    } else {
+        false
+    };
+    let interiors = polygon
+        .interiors()
+        .iter()
+        .map(|ring| LineString(unwrap(&ring.0, anchor)))
+        .collect();
+
+    (Polygon::new(LineString(exterior), interiors), is_polar)
+}
+
+/// Split an unwrapped linestring along the antimeridian.
+///
+/// Parts are then shifted back into the [-180°, 180°] range.
+fn split_line(coords: &[Coord]) -> Vec<LineString> {
+    let mut parts = Vec::new();
+    let mut part = coords.first().copied().into_iter().collect::<Vec<_>>();
+
+    for (&start, &end) in coords.iter().zip(coords.iter().skip(1)) {
+        let (min, max) = (start.x.min(end.x), start.x.max(end.x));
+        // Greatest antimeridian (odd multiple of 180°) below the segment's end.
+        let meridian = (max + 180.).div_euclid(360.).mul_add(360., -180.);
+        if min < meridian && meridian < max {
+            let ratio = (meridian - start.x) / (end.x - start.x);
+            let crossing = Coord {
+                x: meridian,
+                y: ratio.mul_add(end.y - start.y, start.y),
+            };
+            part.push(crossing);
+            parts.push(std::mem::replace(&mut part, vec![crossing]));
+        }
+        part.push(end);
+    }
+    parts.push(part);
+
+    parts
+        .into_iter()
+        .filter(|part| part.len() > 1)
+        .map(|part| {
+            let line = LineString(part);
+            let offset = wrap_offset(&line);
+            line.translate(offset, 0.)
+        })
+        .collect()
+}
+
+/// Split an unwrapped polygon along the antimeridian.
+///
+/// The polygon is clipped by the world and its two neighboring copies, and
+/// each clipped part is shifted back into the [-180°, 180°] range.
+fn split_polygon(polygon: &Polygon) -> MultiPolygon {
+    let Some(bbox) = polygon.bounding_rect() else {
+        return MultiPolygon::new(Vec::new());
+    };
+
+    [-360., 0., 360.]
+        .into_iter()
+        .filter_map(|offset: f64| {
+            // Clip windows are kept as tight as possible since the clipping
+            // precision is relative to their extent.
+            let min = bbox.min().x.max(offset - 180.);
+            let max = bbox.max().x.min(offset + 180.);
+            (min < max).then(|| {
+                let window = Rect::new(
+                    Coord {
+                        x: min,
+                        y: bbox.min().y,
+                    },
+                    Coord {
+                        x: max,
+                        y: bbox.max().y,
+                    },
+                );
+                clamp(
+                    &polygon
+                        .intersection(&window.to_polygon())
+                        .translate(-offset, 0.),
+                )
+            })
+        })
+        .flatten()
+        .collect()
+}
+
+/// Remove the clipping noise around the antimeridian and the poles.
+///
+/// Longitudes within [`SNAP_TOLERANCE`] of the antimeridian are snapped onto
+/// it, so that both sides of a split line up exactly.
+fn clamp(polygons: &MultiPolygon) -> MultiPolygon {
+    polygons.map_coords(|coord| {
+        let x = if 180. - coord.x.abs() < SNAP_TOLERANCE {
+            180_f64.copysign(coord.x)
+        } else {
+            coord.x
+        };
+        Coord {
+            x,
+            y: coord.y.clamp(-90., 90.),
+        }
+    })
+}
+
+/// Returns the longitude offset that brings the line in the [-180°, 180°]
+/// range.
+fn wrap_offset(line: &LineString) -> f64 {
+    let (min, max) = line
+        .coords()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), coord| {
+            (min.min(coord.x), max.max(coord.x))
+        });
+    -360. * (f64::midpoint(min, max) + 180.).div_euclid(360.)
+}
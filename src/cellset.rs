@@ -0,0 +1,179 @@
+use h3o::{CellIndex, Resolution};
+use std::collections::BTreeSet;
+
+/// A set of cells of any resolution, compared by the area they cover.
+///
+/// Cells are kept disjoint: a cell covered by one of its ancestors is dropped.
+/// They are ordered hierarchically, so the descendants of a cell sort right
+/// before it.
+#[derive(Debug, Clone, Default)]
+pub struct CellSet(BTreeSet<CellIndex>);
+
+impl CellSet {
+    /// Returns the number of cells in the set.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns the cells of the set, in hierarchical order.
+    pub fn iter(&self) -> impl Iterator<Item = CellIndex> + '_ {
+        self.0.iter().copied()
+    }
+
+    /// Returns the finest resolution of the set, if not empty.
+    pub fn finest_resolution(&self) -> Option<Resolution> {
+        self.iter().map(CellIndex::resolution).max()
+    }
+
+    /// Add a cell, unless covered, replacing its descendants.
+    ///
+    /// Returns whether the set has changed.
+    pub fn insert(&mut self, cell: CellIndex) -> bool {
+        if self.covers(cell) {
+            return false;
+        }
+        let descendants = self.descendants(cell).collect::<Vec<_>>();
+        for descendant in descendants {
+            self.0.remove(&descendant);
+        }
+        self.0.insert(cell)
+    }
+
+    /// Returns whether the cell, or one of its ancestors, is in the set.
+    pub fn covers(&self, cell: CellIndex) -> bool {
+        Resolution::range(Resolution::Zero, cell.resolution()).any(
+            |resolution| {
+                cell.parent(resolution)
+                    .is_some_and(|ancestor| self.0.contains(&ancestor))
+            },
+        )
+    }
+
+    /// Returns the cells of the set that are strict descendants of the cell.
+    fn descendants(
+        &self,
+        cell: CellIndex,
+    ) -> impl Iterator<Item = CellIndex> + '_ {
+        // The finest center child has the smallest digits of all descendants.
+        let first = cell
+            .center_child(Resolution::Fifteen)
+            .expect("finest resolution");
+        self.0.range(first..cell).copied()
+    }
+
+    /// Returns the area covered by either set.
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        union.extend(other.iter());
+        union
+    }
+
+    /// Returns the area covered by both sets.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intersection = BTreeSet::new();
+        for cell in self.iter() {
+            if other.covers(cell) {
+                intersection.insert(cell);
+            } else {
+                intersection.extend(other.descendants(cell));
+            }
+        }
+        Self(intersection)
+    }
+
+    /// Returns the area covered by this set but not the other one.
+    ///
+    /// Cells partially covered by the other set are split into their
+    /// uncovered descendants.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = BTreeSet::new();
+        for cell in self.iter() {
+            if !other.covers(cell) {
+                other.subtract(cell, &mut difference);
+            }
+        }
+        Self(difference)
+    }
+
+    /// Returns the area covered by exactly one of the sets.
+    pub fn symmetric_difference(&self, other: &Self) -> Self {
+        let mut difference = self.difference(other);
+        difference.0.append(&mut other.difference(self).0);
+        difference
+    }
+
+    /// Add to `out` the parts of a cell, whose ancestors aren't in the set,
+    /// that aren't in the set.
+    fn subtract(&self, cell: CellIndex, out: &mut BTreeSet<CellIndex>) {
+        if self.0.contains(&cell) {
+            return;
+        }
+        match cell.resolution().succ() {
+            Some(resolution) if self.descendants(cell).next().is_some() => {
+                for child in cell.children(resolution) {
+                    self.subtract(child, out);
+                }
+            }
+            _ => {
+                out.insert(cell);
+            }
+        }
+    }
+
+    /// Replace, from the finest resolution up, complete sets of children by
+    /// their parent, down to the given resolution.
+    pub fn compact(&mut self, min_resolution: Resolution) {
+        let Some(first) = min_resolution.succ() else {
+            return;
+        };
+        for resolution in Resolution::range(first, Resolution::Fifteen).rev() {
+            let mut parents = self
+                .iter()
+                .filter(|cell| cell.resolution() == resolution)
+                .filter_map(|cell| cell.parent(resolution.pred()?))
+                .collect::<Vec<_>>();
+            // Siblings are contiguous in hierarchical order.
+            parents.dedup();
+
+            for parent in parents {
+                let count = self
+                    .descendants(parent)
+                    .filter(|cell| cell.resolution() == resolution)
+                    .count();
+                if u64::try_from(count).is_ok_and(|count| {
+                    count == parent.children_count(resolution)
+                }) {
+                    self.insert(parent);
+                }
+            }
+        }
+    }
+
+    /// Returns the cells expanded to a resolution, in hierarchical order.
+    ///
+    /// Cells finer than the resolution are returned as is.
+    pub fn uncompact(
+        &self,
+        resolution: Resolution,
+    ) -> impl Iterator<Item = CellIndex> + '_ {
+        self.iter().flat_map(move |cell| {
+            cell.children(resolution.max(cell.resolution()))
+        })
+    }
+}
+
+impl FromIterator<CellIndex> for CellSet {
+    fn from_iter<T: IntoIterator<Item = CellIndex>>(iter: T) -> Self {
+        let mut set = Self::default();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<CellIndex> for CellSet {
+    fn extend<T: IntoIterator<Item = CellIndex>>(&mut self, iter: T) {
+        for cell in iter {
+            self.insert(cell);
+        }
+    }
+}
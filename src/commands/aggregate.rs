@@ -0,0 +1,328 @@
+//! Aggregate values per cell.
+
+use anyhow::{Context, Error as AnyError, Result as AnyResult, ensure};
+use clap::{Parser, ValueEnum};
+use either::Either;
+use geojson::{JsonObject, JsonValue};
+use h3o::{CellIndex, Resolution};
+use std::{collections::HashMap, fmt, str::FromStr};
+
+/// Aggregates values per cell.
+///
+/// The command reads `lat lng value` lines (or `cell value` lines, with
+/// `--cells`) from stdin until EOF is encountered, and outputs one row per
+/// cell with the requested aggregations, in order. Located by lat/lng, values
+/// are assigned to the cell at the specified resolution; located by cell,
+/// values are assigned to its ancestor at the specified resolution, if any
+/// (cells coarser than the resolution are rejected).
+///
+/// Values are aggregated in a single pass, and are only kept in memory for the
+/// median and percentiles.
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// Resolution (required for lat/lng input).
+    #[arg(short, long, required_unless_present = "cells")]
+    resolution: Option<Resolution>,
+
+    /// Read `cell value` lines instead of `lat lng value` ones.
+    #[arg(long, default_value_t = false)]
+    cells: bool,
+
+    /// Comma-separated aggregations: `sum`, `mean`, `min`, `max`, `count`,
+    /// `stddev` (population standard deviation), `median` and `pNN`
+    /// (percentile `NN`, linearly interpolated, e.g. `p90`), each at most
+    /// once.
+    #[arg(short, long, value_delimiter = ',', default_value = "count,mean")]
+    aggregations: Vec<Aggregation>,
+
+    /// Output format.
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// Prettify the output (JSON and `GeoJSON` only).
+    #[arg(short, long, default_value_t = false)]
+    pretty: bool,
+
+    #[command(flatten)]
+    geojson: crate::geojson::FeatureArgs,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+    Csv,
+    Geojson,
+}
+
+/// Run the `aggregate` command.
+pub fn run(args: &Args) -> AnyResult<()> {
+    let names = args
+        .aggregations
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    for (i, name) in names.iter().enumerate() {
+        ensure!(!names[..i].contains(name), "duplicate aggregation {name}");
+    }
+
+    let keep_values = args.aggregations.iter().any(|aggregation| {
+        matches!(
+            aggregation,
+            Aggregation::Median | Aggregation::Percentile(_)
+        )
+    });
+    let mut accumulators = HashMap::<CellIndex, Accumulator>::new();
+    for row in read_rows(args.cells, args.resolution)? {
+        let (index, value) = row?;
+        accumulators
+            .entry(index)
+            .or_insert_with(|| Accumulator::new(keep_values))
+            .add(value);
+    }
+    let mut rows = accumulators
+        .into_iter()
+        .map(|(index, mut accumulator)| {
+            let values = args
+                .aggregations
+                .iter()
+                .map(|&aggregation| accumulator.get(aggregation))
+                .collect::<Vec<_>>();
+            (index, values)
+        })
+        .collect::<Vec<_>>();
+    rows.sort_unstable_by_key(|row| row.0);
+
+    let properties = |values: Vec<f64>| {
+        names
+            .iter()
+            .cloned()
+            .zip(values.into_iter().map(JsonValue::from))
+            .collect::<JsonObject>()
+    };
+    match args.format {
+        Format::Text => {
+            for (index, values) in rows {
+                let values =
+                    values.iter().map(ToString::to_string).collect::<Vec<_>>();
+                println!("{index} {}", values.join(" "));
+            }
+        }
+        Format::Json => {
+            let rows = rows
+                .into_iter()
+                .map(|(index, values)| {
+                    let mut row = JsonObject::new();
+                    row.insert("cell".to_owned(), index.to_string().into());
+                    row.extend(properties(values));
+                    row
+                })
+                .collect::<Vec<_>>();
+            crate::json::print(&rows, args.pretty)?;
+        }
+        Format::Csv => {
+            println!("cell,{}", names.join(","));
+            for (index, values) in rows {
+                let values =
+                    values.iter().map(ToString::to_string).collect::<Vec<_>>();
+                println!("{index},{}", values.join(","));
+            }
+        }
+        Format::Geojson => {
+            let features = crate::geojson::shapes(
+                rows.into_iter()
+                    .map(|(index, values)| (index, properties(values))),
+                args.geojson,
+            );
+            let geojson =
+                crate::geojson::collection(features, args.geojson.bbox);
+            crate::json::print(&geojson, args.pretty)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Read the `cell value` or `lat lng value` rows from stdin, located at the
+/// resolution.
+fn read_rows(
+    cells: bool,
+    resolution: Option<Resolution>,
+) -> AnyResult<impl Iterator<Item = AnyResult<(CellIndex, f64)>>> {
+    if cells {
+        return Ok(Either::Left(crate::io::read_cell_records().map(
+            move |record| {
+                let (index, fields) = record?;
+                let index = match resolution {
+                    Some(resolution) => {
+                        index.parent(resolution).with_context(|| {
+                            format!(
+                                "cell {index} is coarser than resolution {resolution}"
+                            )
+                        })?
+                    }
+                    None => index,
+                };
+                Ok((index, parse_value(&fields)?))
+            },
+        )));
+    }
+    let resolution = resolution.context("resolution required for lat/lng")?;
+    Ok(Either::Right(crate::io::read_coord_records().map(
+        move |record| {
+            let (coord, fields) = record?;
+            Ok((coord.to_cell(resolution), parse_value(&fields)?))
+        },
+    )))
+}
+
+/// Parse the value following the location of a row.
+fn parse_value(fields: &[String]) -> AnyResult<f64> {
+    let [ref value] = *fields else {
+        anyhow::bail!("expected a single value, got {}", fields.join(" "));
+    };
+    let value = value
+        .parse::<f64>()
+        .with_context(|| format!("cannot parse {value} as value"))?;
+    ensure!(value.is_finite(), "value is not finite");
+
+    Ok(value)
+}
+
+// -----------------------------------------------------------------------------
+
+/// An aggregation of the values of a cell.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Aggregation {
+    Sum,
+    Mean,
+    Min,
+    Max,
+    Count,
+    Stddev,
+    Median,
+    /// Percentile, between 0 and 100.
+    Percentile(f64),
+}
+
+impl FromStr for Aggregation {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "sum" => Self::Sum,
+            "mean" => Self::Mean,
+            "min" => Self::Min,
+            "max" => Self::Max,
+            "count" => Self::Count,
+            "stddev" => Self::Stddev,
+            "median" => Self::Median,
+            _ => {
+                let percentile = s
+                    .strip_prefix('p')
+                    .and_then(|value| value.parse::<f64>().ok())
+                    .with_context(|| format!("unknown aggregation {s}"))?;
+                ensure!(
+                    (0. ..=100.).contains(&percentile),
+                    "percentile out of range: {s}"
+                );
+                Self::Percentile(percentile)
+            }
+        })
+    }
+}
+
+impl fmt::Display for Aggregation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Sum => write!(f, "sum"),
+            Self::Mean => write!(f, "mean"),
+            Self::Min => write!(f, "min"),
+            Self::Max => write!(f, "max"),
+            Self::Count => write!(f, "count"),
+            Self::Stddev => write!(f, "stddev"),
+            Self::Median => write!(f, "median"),
+            Self::Percentile(percentile) => write!(f, "p{percentile}"),
+        }
+    }
+}
+
+/// Running aggregations of the values of a cell.
+struct Accumulator {
+    count: u64,
+    sum: f64,
+    min: f64,
+    max: f64,
+    /// Running mean and sum of squared deviations (Welford's algorithm).
+    mean: f64,
+    m2: f64,
+    /// Values, kept for the median and percentiles only.
+    values: Option<Vec<f64>>,
+    /// Whether the values are sorted.
+    sorted: bool,
+}
+
+impl Accumulator {
+    fn new(keep_values: bool) -> Self {
+        Self {
+            count: 0,
+            sum: 0.,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            mean: 0.,
+            m2: 0.,
+            values: keep_values.then(Vec::new),
+            sorted: false,
+        }
+    }
+
+    #[expect(clippy::cast_precision_loss, reason = "count is small enough")]
+    fn add(&mut self, value: f64) {
+        self.count += 1;
+        self.sum += value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 = delta.mul_add(value - self.mean, self.m2);
+        if let Some(ref mut values) = self.values {
+            values.push(value);
+            self.sorted = false;
+        }
+    }
+
+    #[expect(clippy::cast_precision_loss, reason = "count is small enough")]
+    fn get(&mut self, aggregation: Aggregation) -> f64 {
+        match aggregation {
+            Aggregation::Sum => self.sum,
+            Aggregation::Mean => self.mean,
+            Aggregation::Min => self.min,
+            Aggregation::Max => self.max,
+            Aggregation::Count => self.count as f64,
+            Aggregation::Stddev => (self.m2 / self.count as f64).sqrt(),
+            Aggregation::Median => self.percentile(50.),
+            Aggregation::Percentile(percentile) => self.percentile(percentile),
+        }
+    }
+
+    /// Returns the percentile of the values, linearly interpolated between
+    /// the closest ranks.
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss,
+        reason = "rank is within the values' range"
+    )]
+    fn percentile(&mut self, percentile: f64) -> f64 {
+        let values = self.values.as_mut().expect("values are kept");
+        if !self.sorted {
+            values.sort_unstable_by(f64::total_cmp);
+            self.sorted = true;
+        }
+        let rank = percentile / 100. * (values.len() - 1) as f64;
+        let (lower, upper) =
+            (values[rank.floor() as usize], values[rank.ceil() as usize]);
+
+        (upper - lower).mul_add(rank.fract(), lower)
+    }
+}
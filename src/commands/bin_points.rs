@@ -0,0 +1,96 @@
+//! Count points per cell.
+
+use anyhow::Result as AnyResult;
+use clap::{Parser, ValueEnum};
+use geojson::{JsonObject, JsonValue};
+use h3o::{CellIndex, Resolution};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Counts the points falling in each cell (hex-binning).
+///
+/// The command reads lat/lng pairs from stdin until EOF is encountered, and
+/// outputs the cells at the specified resolution containing at least one
+/// point, along with their number of points. The input is read in a single
+/// pass: memory only grows with the number of distinct cells.
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// Resolution.
+    #[arg(short, long)]
+    resolution: Resolution,
+
+    /// Output format.
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// Prettify the output (JSON and `GeoJSON` only).
+    #[arg(short, long, default_value_t = false)]
+    pretty: bool,
+
+    #[command(flatten)]
+    geojson: crate::geojson::FeatureArgs,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+    Csv,
+    Geojson,
+}
+
+/// Run the `binPoints` command.
+pub fn run(args: &Args) -> AnyResult<()> {
+    let mut counts = HashMap::<CellIndex, u64>::new();
+    for coord in crate::io::read_coords() {
+        *counts.entry(coord?.to_cell(args.resolution)).or_default() += 1;
+    }
+    let mut counts = counts.into_iter().collect::<Vec<_>>();
+    counts.sort_unstable();
+
+    match args.format {
+        Format::Text => {
+            for (index, count) in counts {
+                println!("{index} {count}");
+            }
+        }
+        Format::Json => {
+            #[derive(Serialize)]
+            struct CellCount {
+                cell: crate::json::CellIndex,
+                count: u64,
+            }
+
+            let counts = counts
+                .into_iter()
+                .map(|(index, count)| CellCount {
+                    cell: index.into(),
+                    count,
+                })
+                .collect::<Vec<_>>();
+            crate::json::print(&counts, args.pretty)?;
+        }
+        Format::Csv => {
+            println!("cell,count");
+            for (index, count) in counts {
+                println!("{index},{count}");
+            }
+        }
+        Format::Geojson => {
+            let features = crate::geojson::shapes(
+                counts.into_iter().map(|(index, count)| {
+                    let mut properties = JsonObject::new();
+                    properties
+                        .insert("count".to_owned(), JsonValue::from(count));
+                    (index, properties)
+                }),
+                args.geojson,
+            );
+            let geojson =
+                crate::geojson::collection(features, args.geojson.bbox);
+            crate::json::print(&geojson, args.pretty)?;
+        }
+    }
+
+    Ok(())
+}
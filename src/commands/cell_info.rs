@@ -1,10 +1,11 @@
 //! Expose cell index information.
 
-use anyhow::Result as AnyResult;
+use anyhow::{Context, Result as AnyResult};
+use arrow_array::{ArrayRef, RecordBatch};
 use clap::{Parser, ValueEnum};
 use h3o::{BaseCell, CellIndex, Face, LatLng, Resolution};
 use serde::Serialize;
-use std::fmt;
+use std::{fmt, path::PathBuf};
 
 /// Print a bunch of info on a cell index.
 #[derive(Parser, Debug)]
@@ -20,34 +21,180 @@ pub struct Args {
     /// Prettify the output (JSON only).
     #[arg(short, long, default_value_t = false)]
     pretty: bool,
+
+    /// Output file (Parquet and Arrow only, stdout otherwise).
+    #[arg(short, long)]
+    output: Option<PathBuf>,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
 enum Format {
     Text,
     Json,
+    Parquet,
+    Arrow,
 }
 
 /// Run the `cellInfo` command.
 pub fn run(args: &Args) -> AnyResult<()> {
     let indexes = crate::utils::get_cell_indexes(args.index);
-    let infos = indexes.map(|input| input.map(CellInfo::from));
 
     match args.format {
         Format::Text => {
-            for info in infos {
-                println!("{}", info?);
+            for index in indexes {
+                println!("{}", CellInfo::from(index?));
             }
         }
         Format::Json => {
-            let infos = infos.collect::<AnyResult<Vec<_>>>()?;
+            let infos = indexes
+                .map(|input| input.map(CellInfo::from))
+                .collect::<AnyResult<Vec<_>>>()?;
             crate::json::print(&infos, args.pretty)?;
         }
+        Format::Parquet => {
+            infos_to_table(indexes, args, crate::table::Format::Parquet)?;
+        }
+        Format::Arrow => {
+            infos_to_table(indexes, args, crate::table::Format::Arrow)?;
+        }
     }
 
     Ok(())
 }
 
+/// Write cell infos as a `Parquet` or `Arrow` table.
+///
+/// Cell indexes (and vertexes/edges) are stored as unsigned 64-bit integers.
+fn infos_to_table(
+    indexes: impl IntoIterator<Item = AnyResult<CellIndex>>,
+    args: &Args,
+    format: crate::table::Format,
+) -> AnyResult<()> {
+    let indexes = indexes.into_iter().collect::<AnyResult<Vec<_>>>()?;
+    let cells = || indexes.iter().copied();
+    let centers = cells().map(LatLng::from).collect::<Vec<_>>();
+
+    let mut columns = vec![
+        ("index", crate::table::cells(cells()), false),
+        (
+            "base_cell",
+            crate::table::u8s(cells().map(|index| index.base_cell().into())),
+            false,
+        ),
+        (
+            "resolution",
+            crate::table::u8s(cells().map(|index| index.resolution().into())),
+            false,
+        ),
+        (
+            "lat",
+            crate::table::floats(centers.iter().copied().map(LatLng::lat)),
+            false,
+        ),
+        (
+            "lng",
+            crate::table::floats(centers.iter().copied().map(LatLng::lng)),
+            false,
+        ),
+        (
+            "area",
+            crate::table::floats(cells().map(CellIndex::area_km2)),
+            false,
+        ),
+        (
+            "edge_length",
+            crate::table::floats(
+                cells().map(|index| {
+                    index.edges().next().expect("edge").length_km()
+                }),
+            ),
+            false,
+        ),
+    ];
+    columns.extend(topology_columns(&indexes));
+    columns.extend([
+        (
+            "is_pentagon",
+            crate::table::booleans(cells().map(CellIndex::is_pentagon)),
+            false,
+        ),
+        (
+            "is_class3",
+            crate::table::booleans(
+                cells().map(|index| index.resolution().is_class3()),
+            ),
+            false,
+        ),
+    ]);
+    let batch = RecordBatch::try_from_iter_with_nullable(columns)
+        .context("build cell info table")?;
+
+    crate::table::write(&batch, format, args.output.as_deref())
+}
+
+/// Returns the columns describing the cells' neighborhood and hierarchy.
+fn topology_columns(
+    indexes: &[CellIndex],
+) -> Vec<(&'static str, ArrayRef, bool)> {
+    vec![
+        (
+            "parent",
+            crate::table::nullable_cells(indexes.iter().map(|index| {
+                index
+                    .resolution()
+                    .pred()
+                    .map(|res| index.parent(res).expect("parent"))
+            })),
+            true,
+        ),
+        (
+            "children",
+            crate::table::u64_lists(indexes.iter().map(|index| {
+                index
+                    .resolution()
+                    .succ()
+                    .map(|res| index.children(res).map(u64::from).collect())
+                    .unwrap_or_default()
+            })),
+            false,
+        ),
+        (
+            "neighbors",
+            crate::table::u64_lists(
+                indexes.iter().map(|index| {
+                    index.grid_disk_safe(1).map(u64::from).collect()
+                }),
+            ),
+            false,
+        ),
+        (
+            "vertexes",
+            crate::table::u64_lists(
+                indexes
+                    .iter()
+                    .map(|index| index.vertexes().map(u64::from).collect()),
+            ),
+            false,
+        ),
+        (
+            "edges",
+            crate::table::u64_lists(
+                indexes
+                    .iter()
+                    .map(|index| index.edges().map(u64::from).collect()),
+            ),
+            false,
+        ),
+        (
+            "faces",
+            crate::table::u8_lists(indexes.iter().map(|index| {
+                index.icosahedron_faces().iter().map(u8::from).collect()
+            })),
+            false,
+        ),
+    ]
+}
+
 // -----------------------------------------------------------------------------
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
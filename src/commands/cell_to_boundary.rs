@@ -2,14 +2,16 @@
 
 use anyhow::{Context, Result as AnyResult};
 use clap::{Parser, ValueEnum};
-use geojson::{FeatureCollection, GeoJson};
 use h3o::CellIndex;
-use kml::Kml;
+use std::{io, num::NonZeroUsize, path::PathBuf};
 
 /// Converts indexes to latitude/longitude cell boundaries in degrees.
 ///
 /// This command reads H3 indexes from stdin and outputs the corresponding cell
 /// boundaries to stdout, until EOF is encountered.
+///
+/// For `FlatGeobuf` and `GeoPackage`, cells are written as polygons and any
+/// field following the index on an input line is kept as an attribute.
 #[derive(Parser, Debug)]
 pub struct Args {
     /// Cell index.
@@ -23,6 +25,37 @@ pub struct Args {
     /// Prettify the output (`GeoJSON` only).
     #[arg(short, long, default_value_t = false)]
     pretty: bool,
+
+    /// Output file (required for `GeoPackage`, stdout otherwise).
+    ///
+    /// Only used by the `KMZ`, `FlatGeobuf` and `GeoPackage` formats.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Names of the fields following the index on each input line
+    /// (`FlatGeobuf` and `GeoPackage` only).
+    #[arg(long, value_delimiter = ',')]
+    columns: Vec<String>,
+
+    /// How to handle cells crossing the antimeridian (`GeoJSON` and KML only).
+    #[arg(long, value_enum, default_value_t = crate::antimeridian::Mode::Split)]
+    antimeridian: crate::antimeridian::Mode,
+
+    /// Input field holding the color of each cell, counted from 1 after the
+    /// cell index (KML only).
+    #[arg(long, help_heading = "KML options")]
+    color_field: Option<NonZeroUsize>,
+
+    /// Output cells as closed polygons, that can be filled, instead of
+    /// outlines (KML only).
+    #[arg(long, default_value_t = false, help_heading = "KML options")]
+    polygon: bool,
+
+    #[command(flatten)]
+    geojson: crate::geojson::FeatureArgs,
+
+    #[command(flatten)]
+    style: crate::kml::StyleArgs,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
@@ -30,16 +63,25 @@ enum Format {
     Text,
     Geojson,
     Kml,
+    Kmz,
+    Flatgeobuf,
+    Gpkg,
 }
 
 /// Run the `cellToBoundary` command.
 pub fn run(args: &Args) -> AnyResult<()> {
-    let indexes = crate::utils::get_cell_indexes(args.index);
+    // Layer formats read their input themselves, to keep the extra fields.
+    let indexes = || crate::utils::get_cell_indexes(args.index);
 
     match args.format {
-        Format::Text => boundaries_to_text(indexes),
-        Format::Geojson => boundaries_to_geojson(indexes, args.pretty),
-        Format::Kml => boundaries_to_kml(indexes),
+        Format::Text => boundaries_to_text(indexes()),
+        Format::Geojson => boundaries_to_geojson(indexes(), args),
+        Format::Kml => boundaries_to_kml(args, false),
+        Format::Kmz => boundaries_to_kml(args, true),
+        Format::Flatgeobuf => {
+            boundaries_to_layer(args, crate::layer::Format::Flatgeobuf)
+        }
+        Format::Gpkg => boundaries_to_layer(args, crate::layer::Format::Gpkg),
     }
     .context("cellToBoundary")?;
 
@@ -66,42 +108,73 @@ fn boundaries_to_text(
 /// Print boundaries as geojson.
 fn boundaries_to_geojson(
     indexes: impl IntoIterator<Item = AnyResult<CellIndex>>,
-    pretty: bool,
+    args: &Args,
 ) -> AnyResult<()> {
     let indexes = indexes.into_iter().collect::<AnyResult<Vec<_>>>()?;
-    let features = crate::geojson::boundaries(&indexes);
-    let geojson = GeoJson::FeatureCollection(FeatureCollection {
-        bbox: None,
-        features,
-        foreign_members: None,
-    });
-
-    crate::json::print(&geojson, pretty)
+    let features =
+        crate::geojson::boundaries(&indexes, args.geojson, args.antimeridian);
+    let geojson = crate::geojson::collection(features, args.geojson.bbox);
+
+    crate::json::print(&geojson, args.pretty)
 }
 
-/// Print boundaries as KML.
-fn boundaries_to_kml(
-    indexes: impl IntoIterator<Item = AnyResult<CellIndex>>,
+/// Print boundaries as KML, or write them as KMZ if `compressed` is set.
+fn boundaries_to_kml(args: &Args, compressed: bool) -> AnyResult<()> {
+    let cells = crate::utils::get_cell_records(args.index)
+        .map(|record| {
+            record.and_then(|(index, fields)| {
+                let color =
+                    crate::kml::cell_color(index, &fields, args.color_field)?;
+                Ok((index, color))
+            })
+        })
+        .collect::<AnyResult<Vec<_>>>()?;
+    let (mut elements, style_urls) = crate::kml::cell_styles(
+        "lineStyle",
+        cells.iter().map(|cell| cell.1),
+        |id, color| vec![args.style.shape_style(id, color)],
+    );
+    let cells = cells
+        .into_iter()
+        .map(|(index, _)| index)
+        .zip(style_urls)
+        .collect::<Vec<_>>();
+    elements.append(&mut crate::kml::boundaries(
+        &cells,
+        args.polygon,
+        args.antimeridian,
+    ));
+
+    let name = args.style.name.clone();
+    let description = args.style.description("cellToBoundary");
+    if !compressed {
+        return crate::kml::print_document(name, description, elements);
+    }
+    match args.output.as_deref() {
+        Some(path) => crate::kml::write_kmz(
+            crate::io::create(path)?,
+            name,
+            description,
+            elements,
+        ),
+        None => crate::kml::write_kmz(
+            io::stdout().lock(),
+            name,
+            description,
+            elements,
+        ),
+    }
+}
+
+/// Print boundaries as a `FlatGeobuf` or `GeoPackage` layer.
+fn boundaries_to_layer(
+    args: &Args,
+    format: crate::layer::Format,
 ) -> AnyResult<()> {
-    let indexes = indexes.into_iter().collect::<AnyResult<Vec<_>>>()?;
-    let style_id = "lineStyle1";
-    let style = kml::types::Style {
-        id: Some(style_id.to_owned()),
-        line: Some(kml::types::LineStyle {
-            id: Some("lineStyle2".to_owned()),
-            color: "ff0000ff".to_owned(),
-            width: 2.,
-            ..kml::types::LineStyle::default()
-        }),
-        ..kml::types::Style::default()
-    };
-
-    let mut elements = vec![Kml::Style(style)];
-    elements.append(&mut crate::kml::boundaries(&indexes, style_id));
-
-    crate::kml::print_document(
-        "H3 Geometry".to_owned(),
-        "Generated by cellToBoundary".to_owned(),
-        elements,
-    )
+    let cells = crate::utils::get_cell_records(args.index)
+        .collect::<AnyResult<Vec<_>>>()?;
+    let layer =
+        crate::layer::boundaries("cells".to_owned(), cells, &args.columns);
+
+    crate::layer::write(layer, format, args.output.as_deref())
 }
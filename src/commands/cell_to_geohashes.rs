@@ -0,0 +1,103 @@
+//! Convert cells into geohashes.
+
+use crate::geohash::Geohash;
+use anyhow::Result as AnyResult;
+use clap::{Parser, ValueEnum};
+use geo::{Area, BooleanOps, BoundingRect};
+use h3o::CellIndex;
+use serde::Serialize;
+
+/// Converts indexes into the geohashes covering their boundary.
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// Cell index.
+    #[arg(short, long)]
+    index: Option<CellIndex>,
+
+    /// Length of the geohashes.
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=i64::from(crate::geohash::MAX_PRECISION)))]
+    precision: u8,
+
+    /// Output format.
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// Prettify the output (JSON only).
+    #[arg(short, long, default_value_t = false)]
+    pretty: bool,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+}
+
+/// Run the `cellToGeohashes` command.
+pub fn run(args: &Args) -> AnyResult<()> {
+    let indexes = crate::utils::get_cell_indexes(args.index).map(|index| {
+        index.map(|index| (index, cell_to_geohashes(index, args.precision)))
+    });
+
+    match args.format {
+        Format::Text => {
+            for result in indexes {
+                let (_, geohashes) = result?;
+                for geohash in geohashes {
+                    println!("{geohash}");
+                }
+            }
+        }
+        Format::Json => {
+            #[derive(Serialize)]
+            struct CellGeohashes {
+                cell: crate::json::CellIndex,
+                geohashes: Vec<String>,
+            }
+
+            let indexes = indexes
+                .map(|result| {
+                    result.map(|(index, geohashes)| CellGeohashes {
+                        cell: index.into(),
+                        geohashes,
+                    })
+                })
+                .collect::<AnyResult<Vec<_>>>()?;
+
+            crate::json::print(&indexes, args.pretty)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the geohashes intersecting a cell, sorted.
+///
+/// Geohashes merely touching the cell are excluded.
+fn cell_to_geohashes(index: CellIndex, precision: u8) -> Vec<String> {
+    let mut geohashes = Vec::new();
+    for polygon in crate::antimeridian::cell(index) {
+        let Some(rect) = polygon.bounding_rect() else {
+            continue;
+        };
+        let south_west = Geohash::containing(rect.min(), precision);
+        let north_east = Geohash::containing(rect.max(), precision);
+
+        for x in south_west.x..=north_east.x {
+            for y in south_west.y..=north_east.y {
+                let geohash = Geohash { precision, x, y };
+                if polygon
+                    .intersection(&geohash.bounds().to_polygon())
+                    .unsigned_area()
+                    > 0.
+                {
+                    geohashes.push(geohash.to_string());
+                }
+            }
+        }
+    }
+    geohashes.sort_unstable();
+    geohashes.dedup();
+
+    geohashes
+}
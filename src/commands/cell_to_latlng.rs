@@ -2,9 +2,8 @@
 
 use anyhow::{Context, Result as AnyResult};
 use clap::{Parser, ValueEnum};
-use geojson::{FeatureCollection, GeoJson};
 use h3o::{CellIndex, LatLng};
-use kml::Kml;
+use std::num::NonZeroUsize;
 
 /// Converts indexes to latitude/longitude center coordinates in degrees.
 ///
@@ -23,6 +22,17 @@ pub struct Args {
     /// Prettify the output.
     #[arg(short, long, default_value_t = false)]
     pretty: bool,
+
+    /// Input field holding the color of each cell, counted from 1 after the
+    /// cell index (KML only).
+    #[arg(long, help_heading = "KML options")]
+    color_field: Option<NonZeroUsize>,
+
+    #[command(flatten)]
+    geojson: crate::geojson::FeatureArgs,
+
+    #[command(flatten)]
+    style: crate::kml::StyleArgs,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
@@ -35,13 +45,14 @@ enum Format {
 
 /// Run the `cellToLatLng` command.
 pub fn run(args: &Args) -> AnyResult<()> {
-    let indexes = crate::utils::get_cell_indexes(args.index);
+    // KML reads its input itself, to keep the color field.
+    let indexes = || crate::utils::get_cell_indexes(args.index);
 
     match args.format {
-        Format::Text => latlng_to_text(indexes),
-        Format::Json => latlng_to_json(indexes, args.pretty),
-        Format::Geojson => latlng_to_geojson(indexes, args.pretty),
-        Format::Kml => latlng_to_kml(indexes),
+        Format::Text => latlng_to_text(indexes()),
+        Format::Json => latlng_to_json(indexes(), args.pretty),
+        Format::Geojson => latlng_to_geojson(indexes(), args),
+        Format::Kml => latlng_to_kml(args),
     }
     .context("cellToLatLng")?;
 
@@ -76,98 +87,41 @@ fn latlng_to_json(
 /// Print lat/lng as geojson.
 fn latlng_to_geojson(
     indexes: impl IntoIterator<Item = AnyResult<CellIndex>>,
-    pretty: bool,
+    args: &Args,
 ) -> AnyResult<()> {
     let indexes = indexes.into_iter().collect::<AnyResult<Vec<_>>>()?;
-    let features = crate::geojson::centers(&indexes);
-    let geojson = GeoJson::FeatureCollection(FeatureCollection {
-        bbox: None,
-        features,
-        foreign_members: None,
-    });
-
-    crate::json::print(&geojson, pretty)
+    let features = crate::geojson::centers(&indexes, args.geojson);
+    let geojson = crate::geojson::collection(features, args.geojson.bbox);
+
+    crate::json::print(&geojson, args.pretty)
 }
 
 /// Print lat/lng as KML.
-fn latlng_to_kml(
-    indexes: impl IntoIterator<Item = AnyResult<CellIndex>>,
-) -> AnyResult<()> {
-    let indexes = indexes.into_iter().collect::<AnyResult<Vec<_>>>()?;
-    // Define styles.
-    let style = kml::types::Style {
-        id: Some("s_circle".to_owned()),
-        icon: Some(kml::types::IconStyle{
-            scale: 1.1,
-            icon: kml::types::Icon {
-                href: "http://maps.google.com/mapfiles/kml/shapes/placemark_circle.png".to_owned(),
-                ..kml::types::Icon::default()
-            },
-            hot_spot: Some(kml::types::Vec2 {
-                x: 20.,
-                y: 2.,
-                xunits: kml::types::Units::Pixels,
-                yunits: kml::types::Units::Pixels,
-            }),
-            ..kml::types::IconStyle::default()
-        }),
-        label: Some(kml::types::LabelStyle{
-            color: "ff0000ff".to_owned(),
-            scale: 2.,
-            ..kml::types::LabelStyle::default()
-        }),
-        ..kml::types::Style::default()
-    };
-    let style_hl = kml::types::Style {
-        id: Some("s_circle_hl".to_owned()),
-        icon: Some(kml::types::IconStyle{
-            scale: 1.3,
-            icon: kml::types::Icon {
-                href: "http://maps.google.com/mapfiles/kml/shapes/placemark_circle.png".to_owned(),
-                ..kml::types::Icon::default()
-            },
-            hot_spot: Some(kml::types::Vec2 {
-                x: 20.,
-                y: 2.,
-                xunits: kml::types::Units::Pixels,
-                yunits: kml::types::Units::Pixels,
-            }),
-            ..kml::types::IconStyle::default()
-        }),
-        label: Some(kml::types::LabelStyle{
-            color: "ff0000ff".to_owned(),
-            scale: 2.,
-            ..kml::types::LabelStyle::default()
-        }),
-        ..kml::types::Style::default()
-    };
-    let style_id = "m_ylw-pushpin";
-    let style_map = kml::types::StyleMap {
-        id: Some(style_id.to_owned()),
-        pairs: vec![
-            kml::types::Pair {
-                key: "normal".to_owned(),
-                style_url: "#s_circle".to_owned(),
-                ..kml::types::Pair::default()
-            },
-            kml::types::Pair {
-                key: "highlight".to_owned(),
-                style_url: "#s_circle_hl".to_owned(),
-                ..kml::types::Pair::default()
-            },
-        ],
-        ..kml::types::StyleMap::default()
-    };
-    let mut elements = vec![
-        Kml::Style(style),
-        Kml::Style(style_hl),
-        Kml::StyleMap(style_map),
-    ];
-    elements.append(&mut crate::kml::centers(&indexes, style_id));
+fn latlng_to_kml(args: &Args) -> AnyResult<()> {
+    let cells = crate::utils::get_cell_records(args.index)
+        .map(|record| {
+            record.and_then(|(index, fields)| {
+                let color =
+                    crate::kml::cell_color(index, &fields, args.color_field)?;
+                Ok((index, color))
+            })
+        })
+        .collect::<AnyResult<Vec<_>>>()?;
+    let (mut elements, style_urls) = crate::kml::cell_styles(
+        "m_ylw-pushpin",
+        cells.iter().map(|cell| cell.1),
+        |id, color| args.style.icon_style(id, color),
+    );
+    let cells = cells
+        .into_iter()
+        .map(|(index, _)| index)
+        .zip(style_urls)
+        .collect::<Vec<_>>();
+    elements.append(&mut crate::kml::centers(&cells));
 
     crate::kml::print_document(
-        "H3 Geometry".to_owned(),
-        "Generated by cellToLatLng".to_owned(),
+        args.style.name.clone(),
+        args.style.description("cellToLatLng"),
         elements,
     )
 }
@@ -1,9 +1,11 @@
 //! Expose [`CellIndex::parent`]
 
-use anyhow::Result as AnyResult;
+use anyhow::{Context, Result as AnyResult};
+use arrow_array::RecordBatch;
 use clap::{Parser, ValueEnum};
 use h3o::{CellIndex, Resolution};
 use serde::Serialize;
+use std::path::PathBuf;
 
 /// Converts an index into its parent.
 ///
@@ -28,12 +30,18 @@ pub struct Args {
     /// Prettify the output (JSON only).
     #[arg(short, long, default_value_t = false)]
     pretty: bool,
+
+    /// Output file (Parquet and Arrow only, stdout otherwise).
+    #[arg(short, long)]
+    output: Option<PathBuf>,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
 enum Format {
     Text,
     Json,
+    Parquet,
+    Arrow,
 }
 
 /// Run the `cellToParent` command.
@@ -66,7 +74,33 @@ pub fn run(args: &Args) -> AnyResult<()> {
 
             crate::json::print(&indexes, args.pretty)?;
         }
+        Format::Parquet => {
+            parents_to_table(indexes, args, crate::table::Format::Parquet)?;
+        }
+        Format::Arrow => {
+            parents_to_table(indexes, args, crate::table::Format::Arrow)?;
+        }
     }
 
     Ok(())
 }
+
+/// Write the children and their parent as a `Parquet` or `Arrow` table.
+fn parents_to_table(
+    indexes: impl IntoIterator<Item = AnyResult<(CellIndex, Option<CellIndex>)>>,
+    args: &Args,
+    format: crate::table::Format,
+) -> AnyResult<()> {
+    let (children, parents): (Vec<_>, Vec<_>) = indexes
+        .into_iter()
+        .collect::<AnyResult<Vec<_>>>()?
+        .into_iter()
+        .unzip();
+    let batch = RecordBatch::try_from_iter_with_nullable([
+        ("child", crate::table::cells(children), false),
+        ("parent", crate::table::nullable_cells(parents), true),
+    ])
+    .context("build parent table")?;
+
+    crate::table::write(&batch, format, args.output.as_deref())
+}
@@ -2,13 +2,21 @@
 
 use anyhow::{Context, Result as AnyResult};
 use clap::{Parser, ValueEnum};
-use geojson::Feature;
+use geo_types::{Geometry, MultiPolygon};
+use geojson::{Feature, JsonObject, JsonValue};
 use h3o::{CellIndex, geom::SolventBuilder};
-use kml::Kml;
+use std::{
+    collections::BTreeMap,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+};
 
 /// Converts indexes to (multi)polygon.
 ///
-/// All indexes must have the same resolution.
+/// Indexes may have different resolutions (e.g. compacted sets): they are
+/// expanded to the finest resolution present before being dissolved. With
+/// `--group-by`, the command outputs one (multi)polygon per group, as a feature
+/// collection in `GeoJSON`.
 #[derive(Parser, Debug)]
 pub struct Args {
     /// Cell index.
@@ -22,61 +30,263 @@ pub struct Args {
     /// Prettify the output (`GeoJSON` only)
     #[arg(short, long, default_value_t = false)]
     pretty: bool,
+
+    /// Output file (required for `GeoPackage`, stdout otherwise).
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// How to handle polygons crossing the antimeridian (`GeoJSON` and KML
+    /// only).
+    #[arg(long, value_enum, default_value_t = crate::antimeridian::Mode::Split)]
+    antimeridian: crate::antimeridian::Mode,
+
+    /// Add a bounding box to the features (`GeoJSON` only).
+    #[arg(long, default_value_t = false)]
+    bbox: bool,
+
+    /// Input field to group cells by, counted from 1 after the cell index.
+    ///
+    /// Each group is dissolved separately, and its polygons are tagged with
+    /// the group key.
+    #[arg(long)]
+    group_by: Option<NonZeroUsize>,
+
+    /// Simplify the polygons with the given algorithm (`GeoJSON` and KML
+    /// only).
+    #[arg(long, value_enum, help_heading = "Simplification options")]
+    simplify: Option<crate::simplify::Algorithm>,
+
+    /// Simplification tolerance, in meters.
+    #[arg(
+        long,
+        default_value_t = 10.,
+        requires = "simplify",
+        help_heading = "Simplification options"
+    )]
+    tolerance: f64,
+
+    /// Number of Chaikin smoothing iterations (`GeoJSON` and KML only).
+    #[arg(long, default_value_t = 0, help_heading = "Simplification options")]
+    smooth: usize,
+
+    #[command(flatten)]
+    style: crate::kml::StyleArgs,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
 enum Format {
     Geojson,
     Kml,
+    Flatgeobuf,
+    Gpkg,
+}
+
+/// Cells dissolved together.
+struct Group {
+    /// Group key, if grouped by a field.
+    key: Option<String>,
+    /// Cells of the group.
+    cells: Vec<CellIndex>,
+}
+
+impl Group {
+    /// Returns a new group, with its cells normalized to a single resolution.
+    fn new(key: Option<String>, cells: Vec<CellIndex>) -> AnyResult<Self> {
+        Ok(Self {
+            key,
+            cells: crate::utils::uncompact_to_finest(cells)?,
+        })
+    }
 }
 
 /// Run the `cellToPolygon` command.
 pub fn run(args: &Args) -> AnyResult<()> {
-    let indexes = crate::utils::get_cell_indexes(args.index)
-        .collect::<AnyResult<Vec<_>>>()?;
-    let solvent = SolventBuilder::new().build();
+    let groups = read_groups(args)?;
 
     match args.format {
-        Format::Geojson => {
-            let geometry =
-                solvent.dissolve(indexes).context("compute GeoJSON")?;
-            let feature = Feature {
-                bbox: None,
+        Format::Geojson => polygons_to_geojson(groups, args),
+        Format::Kml => polygons_to_kml(groups, args),
+        Format::Flatgeobuf => polygons_to_layer(
+            groups,
+            args.group_by.is_some(),
+            crate::layer::Format::Flatgeobuf,
+            args.output.as_deref(),
+        ),
+        Format::Gpkg => polygons_to_layer(
+            groups,
+            args.group_by.is_some(),
+            crate::layer::Format::Gpkg,
+            args.output.as_deref(),
+        ),
+    }
+}
+
+/// Read the input cells, grouped by key if `--group-by` is set.
+///
+/// Groups are sorted by key.
+fn read_groups(args: &Args) -> AnyResult<Vec<Group>> {
+    let Some(field) = args.group_by else {
+        let cells = crate::utils::get_cell_indexes(args.index)
+            .collect::<AnyResult<Vec<_>>>()?;
+        return Ok(vec![Group::new(None, cells)?]);
+    };
+
+    let mut groups = BTreeMap::<String, Vec<CellIndex>>::new();
+    for record in crate::utils::get_cell_records(args.index) {
+        let (index, fields) = record?;
+        let key = fields
+            .into_iter()
+            .nth(field.get() - 1)
+            .with_context(|| format!("missing group field for {index}"))?;
+        groups.entry(key).or_default().push(index);
+    }
+
+    groups
+        .into_iter()
+        .map(|(key, cells)| Group::new(Some(key), cells))
+        .collect()
+}
+
+/// Print the polygons as `GeoJSON`.
+///
+/// Grouped polygons are printed as a feature collection, with the group key as
+/// `key` property.
+fn polygons_to_geojson(groups: Vec<Group>, args: &Args) -> AnyResult<()> {
+    let mut features = dissolve(groups, args)?
+        .into_iter()
+        .map(|(key, polygons)| {
+            let geometry = Geometry::MultiPolygon(polygons);
+            let properties = key.map(|key| {
+                JsonObject::from_iter([(
+                    "key".to_owned(),
+                    JsonValue::from(key),
+                )])
+            });
+            Feature {
+                bbox: args
+                    .bbox
+                    .then(|| crate::geojson::bbox(&geometry))
+                    .flatten(),
                 geometry: Some((&geometry).into()),
                 id: None,
-                properties: None,
+                properties,
                 foreign_members: None,
-            };
-            crate::json::print(&feature, args.pretty)?;
-        }
-        Format::Kml => {
-            let style_id = "lineStyle1";
-            let style = kml::types::Style {
-                id: Some(style_id.to_owned()),
-                line: Some(kml::types::LineStyle {
-                    id: Some("lineStyle2".to_owned()),
-                    color: "ff0000ff".to_owned(),
-                    width: 2.,
-                    ..kml::types::LineStyle::default()
-                }),
-                ..kml::types::Style::default()
-            };
-
-            let elements = vec![
-                Kml::Style(style),
-                crate::kml::polygons(
-                    solvent.dissolve(indexes).context("compute polygons")?,
-                    style_id,
-                ),
-            ];
-
-            crate::kml::print_document(
-                "H3 Geometry".to_owned(),
-                "Generated by cellToPolygon".to_owned(),
-                elements,
-            )?;
-        }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if args.group_by.is_none()
+        && let Some(feature) = features.pop()
+    {
+        return crate::json::print(&feature, args.pretty);
+    }
+    let geojson = crate::geojson::collection(features, args.bbox);
+
+    crate::json::print(&geojson, args.pretty)
+}
+
+/// Print the polygons as KML, with one placemark per group.
+fn polygons_to_kml(groups: Vec<Group>, args: &Args) -> AnyResult<()> {
+    let style_id = "lineStyle";
+    let mut elements = vec![args.style.shape_style(style_id.to_owned(), None)];
+    for (key, polygons) in dissolve(groups, args)? {
+        elements.push(crate::kml::polygons(polygons, key, style_id));
     }
 
-    Ok(())
+    crate::kml::print_document(
+        args.style.name.clone(),
+        args.style.description("cellToPolygon"),
+        elements,
+    )
+}
+
+/// Dissolve the groups into polygons, for `GeoJSON` and KML outputs.
+///
+/// Polygons are simplified (all groups at once, to keep their shared
+/// boundaries) and smoothed as requested, before being fixed around the
+/// antimeridian.
+fn dissolve(
+    groups: Vec<Group>,
+    args: &Args,
+) -> AnyResult<Vec<(Option<String>, MultiPolygon)>> {
+    let solvent = SolventBuilder::new().build();
+    let (keys, mut polygons) = groups
+        .into_iter()
+        .map(|group| {
+            let polygons =
+                solvent.dissolve(group.cells).context("compute polygons")?;
+            Ok((group.key, polygons))
+        })
+        .collect::<AnyResult<(Vec<_>, Vec<_>)>>()?;
+    if let Some(algorithm) = args.simplify {
+        polygons =
+            crate::simplify::simplify(polygons, algorithm, args.tolerance);
+    }
+
+    Ok(keys
+        .into_iter()
+        .zip(polygons)
+        .map(|(key, mut polygons)| {
+            if args.smooth > 0 {
+                polygons = crate::simplify::smooth(polygons, args.smooth);
+            }
+            let polygons =
+                crate::antimeridian::multi_polygon(polygons, args.antimeridian);
+            (key, polygons)
+        })
+        .collect())
+}
+
+/// Write the polygons as a `FlatGeobuf` or `GeoPackage` layer.
+///
+/// The layer contains a `MultiPolygon` feature per group, with the resolution
+/// and the number of cells as attributes, preceded by the group key if
+/// `grouped` is set.
+fn polygons_to_layer(
+    groups: Vec<Group>,
+    grouped: bool,
+    format: crate::layer::Format,
+    output: Option<&Path>,
+) -> AnyResult<()> {
+    let solvent = SolventBuilder::new().build();
+    let mut columns = vec![
+        crate::layer::Column::integer("resolution"),
+        crate::layer::Column::integer("count"),
+    ];
+    if grouped {
+        columns.insert(0, crate::layer::Column::text("key"));
+    }
+    let features = groups
+        .into_iter()
+        .map(|group| {
+            let resolution = group.cells.first().map_or(
+                crate::layer::Value::Null,
+                |index| {
+                    crate::layer::Value::Integer(
+                        u8::from(index.resolution()).into(),
+                    )
+                },
+            );
+            let count =
+                i64::try_from(group.cells.len()).context("too many cells")?;
+            let geometry =
+                solvent.dissolve(group.cells).context("compute polygons")?;
+            let mut values =
+                vec![resolution, crate::layer::Value::Integer(count)];
+            if let Some(key) = group.key {
+                values.insert(0, crate::layer::Value::Text(key));
+            }
+            Ok(crate::layer::Feature {
+                geometry: geometry.into(),
+                values,
+            })
+        })
+        .collect::<AnyResult<Vec<_>>>()?;
+    let layer = crate::layer::Layer {
+        name: "polygons".to_owned(),
+        columns,
+        features,
+    };
+
+    crate::layer::write(layer, format, output)
 }
@@ -0,0 +1,146 @@
+//! Convert cells into Web Mercator tiles.
+
+use crate::tile::Tile;
+use anyhow::{Result as AnyResult, ensure};
+use clap::{Parser, ValueEnum};
+use geo::{Area, BooleanOps, BoundingRect};
+use geo_types::Coord;
+use h3o::CellIndex;
+use serde::Serialize;
+
+/// Converts indexes into the Web Mercator tiles they intersect.
+///
+/// Tiles are printed as `z/x/y` coordinates (XYZ scheme) or as quadkeys. Web
+/// Mercator stops at ±85.05° of latitude: unless `--include-poles` is set,
+/// the parts of the cells beyond are ignored, and cells lying entirely beyond
+/// have no tile.
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// Cell index.
+    #[arg(short, long)]
+    index: Option<CellIndex>,
+
+    /// Zoom level of the tiles.
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(0..=i64::from(crate::tile::MAX_ZOOM)))]
+    zoom: u8,
+
+    /// Extend the first and last rows of tiles to the poles.
+    #[arg(long, default_value_t = false)]
+    include_poles: bool,
+
+    /// Print quadkeys instead of `z/x/y` coordinates (zoom 1 and above).
+    #[arg(short, long, default_value_t = false)]
+    quadkey: bool,
+
+    /// Output format.
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// Prettify the output (JSON only).
+    #[arg(short, long, default_value_t = false)]
+    pretty: bool,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+}
+
+/// Run the `cellToTiles` command.
+pub fn run(args: &Args) -> AnyResult<()> {
+    ensure!(
+        !args.quadkey || args.zoom > 0,
+        "quadkeys require a zoom level of at least 1"
+    );
+    let indexes = crate::utils::get_cell_indexes(args.index)
+        .map(|index| index.map(|index| (index, cell_to_tiles(index, args))));
+    let name = |tile: Tile| {
+        if args.quadkey {
+            tile.quadkey()
+        } else {
+            tile.to_string()
+        }
+    };
+
+    match args.format {
+        Format::Text => {
+            for result in indexes {
+                let (_, tiles) = result?;
+                for tile in tiles {
+                    println!("{}", name(tile));
+                }
+            }
+        }
+        Format::Json => {
+            #[derive(Serialize)]
+            struct CellTiles {
+                cell: crate::json::CellIndex,
+                tiles: Vec<String>,
+            }
+
+            let indexes = indexes
+                .map(|result| {
+                    result.map(|(index, tiles)| CellTiles {
+                        cell: index.into(),
+                        tiles: tiles.into_iter().map(name).collect(),
+                    })
+                })
+                .collect::<AnyResult<Vec<_>>>()?;
+
+            crate::json::print(&indexes, args.pretty)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the tiles intersecting a cell, sorted.
+///
+/// Tiles merely touching the cell are excluded.
+fn cell_to_tiles(index: CellIndex, args: &Args) -> Vec<Tile> {
+    let mut tiles = Vec::new();
+    for polygon in crate::antimeridian::cell(index) {
+        let Some(rect) = polygon.bounding_rect() else {
+            continue;
+        };
+        let north_west = Tile::containing(
+            Coord {
+                x: rect.min().x,
+                y: rect.max().y,
+            },
+            args.zoom,
+        );
+        let south_east = Tile::containing(
+            Coord {
+                x: rect.max().x,
+                y: rect.min().y,
+            },
+            args.zoom,
+        );
+
+        for x in north_west.x..=south_east.x {
+            for y in north_west.y..=south_east.y {
+                let tile = Tile {
+                    zoom: args.zoom,
+                    x,
+                    y,
+                };
+                let bounds = if args.include_poles {
+                    tile.extended_bounds()
+                } else {
+                    tile.bounds()
+                };
+                if polygon.intersection(&bounds.to_polygon()).unsigned_area()
+                    > 0.
+                {
+                    tiles.push(tile);
+                }
+            }
+        }
+    }
+    tiles.sort_unstable();
+    tiles.dedup();
+
+    tiles
+}
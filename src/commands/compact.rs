@@ -1,14 +1,28 @@
-//! Expose [`CellIndex::compact`]
+//! Compact sets of cells.
 
-use anyhow::{Context, Result as AnyResult};
+use crate::cellset::CellSet;
+use anyhow::{Result as AnyResult, ensure};
 use clap::{Parser, ValueEnum};
-use h3o::CellIndex;
+use h3o::Resolution;
 
 /// Compact the given set of indexes (from stdin).
 ///
-/// All indexes must have the same resolution.
+/// Indexes may have mixed resolutions (e.g. already compacted cells):
+/// duplicates and cells covered by one of their ancestors are dropped, and
+/// complete sets of siblings are merged into their parent, whatever their
+/// resolution.
 #[derive(Parser, Debug)]
 pub struct Args {
+    /// Coarsest resolution of the output: cells are never merged beyond it,
+    /// and coarser input cells are expanded to it.
+    #[arg(long, default_value_t = Resolution::Zero)]
+    min_resolution: Resolution,
+
+    /// Finest resolution of the output: finer input cells are first replaced
+    /// by their ancestor at this resolution, which may grow the covered area.
+    #[arg(long, default_value_t = Resolution::Fifteen)]
+    max_resolution: Resolution,
+
     /// Output format.
     #[arg(short, long, value_enum, default_value_t = Format::Text)]
     format: Format,
@@ -26,10 +40,21 @@ enum Format {
 
 /// Run the `compact` command.
 pub fn run(args: &Args) -> AnyResult<()> {
-    let mut indexes =
-        crate::io::read_cell_indexes().collect::<AnyResult<Vec<_>>>()?;
+    ensure!(
+        args.min_resolution <= args.max_resolution,
+        "min resolution ({}) is finer than max resolution ({})",
+        args.min_resolution,
+        args.max_resolution
+    );
+    let mut set = crate::io::read_cell_indexes()
+        .map(|index| {
+            index
+                .map(|index| index.parent(args.max_resolution).unwrap_or(index))
+        })
+        .collect::<AnyResult<CellSet>>()?;
 
-    CellIndex::compact(&mut indexes).context("compaction")?;
+    set.compact(args.min_resolution);
+    let indexes = set.uncompact(args.min_resolution);
     match args.format {
         Format::Text => {
             for index in indexes {
@@ -38,7 +63,6 @@ pub fn run(args: &Args) -> AnyResult<()> {
         }
         Format::Json => {
             let compacted = indexes
-                .into_iter()
                 .map(Into::into)
                 .collect::<Vec<crate::json::CellIndex>>();
             crate::json::print(&compacted, args.pretty)?;
@@ -1,8 +1,12 @@
 //! Expose [`h3o_zip::decompress`]
 
 use anyhow::{Context, Result as AnyResult};
+use arrow_array::RecordBatch;
 use clap::{Parser, ValueEnum};
-use std::io::{self, Read};
+use std::{
+    io::{self, Read},
+    path::PathBuf,
+};
 
 /// Decompress and print the cell indexes from the compressed input on stdin.
 #[derive(Parser, Debug)]
@@ -14,15 +18,21 @@ pub struct Args {
     /// Prettify the output (JSON only).
     #[arg(short, long, default_value_t = false)]
     pretty: bool,
+
+    /// Output file (Parquet and Arrow only, stdout otherwise).
+    #[arg(short, long)]
+    output: Option<PathBuf>,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
 enum Format {
     Text,
     Json,
+    Parquet,
+    Arrow,
 }
 
-/// Run the `cellToPolygon` command.
+/// Run the `decompress` command.
 pub fn run(args: &Args) -> AnyResult<()> {
     let mut bytes = Vec::new();
     io::stdin()
@@ -43,7 +53,33 @@ pub fn run(args: &Args) -> AnyResult<()> {
                 .context("decompress")?;
             crate::json::print(&indexes, args.pretty)?;
         }
+        Format::Parquet => {
+            cells_to_table(indexes, args, crate::table::Format::Parquet)?;
+        }
+        Format::Arrow => {
+            cells_to_table(indexes, args, crate::table::Format::Arrow)?;
+        }
     }
 
     Ok(())
 }
+
+/// Write the decompressed cells as a `Parquet` or `Arrow` table.
+fn cells_to_table<E>(
+    indexes: impl IntoIterator<Item = Result<h3o::CellIndex, E>>,
+    args: &Args,
+    format: crate::table::Format,
+) -> AnyResult<()>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let indexes = indexes
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .context("decompress")?;
+    let batch =
+        RecordBatch::try_from_iter([("cell", crate::table::cells(indexes))])
+            .context("build cell table")?;
+
+    crate::table::write(&batch, format, args.output.as_deref())
+}
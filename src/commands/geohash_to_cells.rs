@@ -0,0 +1,95 @@
+//! Expose [`TilerBuilder`] over geohashes.
+
+use crate::geohash::Geohash;
+use anyhow::{Context, Result as AnyResult};
+use clap::{Parser, ValueEnum};
+use h3o::{CellIndex, Resolution, geom::TilerBuilder};
+use serde::Serialize;
+
+/// Converts geohashes into the cells covering them.
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// Geohash.
+    #[arg(short, long)]
+    geohash: Option<Geohash>,
+
+    /// Target resolution.
+    #[arg(short, long)]
+    resolution: Resolution,
+
+    /// Polyfill mode.
+    #[arg(short, long, value_enum, default_value_t = crate::polyfill::Mode::ContainsCentroid)]
+    mode: crate::polyfill::Mode,
+
+    /// Output format.
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// Prettify the output (JSON only).
+    #[arg(short, long, default_value_t = false)]
+    pretty: bool,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+}
+
+/// Run the `geohashToCells` command.
+pub fn run(args: &Args) -> AnyResult<()> {
+    let geohashes = crate::utils::get_geohashes(args.geohash).map(|geohash| {
+        geohash
+            .and_then(|geohash| Ok((geohash, geohash_to_cells(geohash, args)?)))
+    });
+
+    match args.format {
+        Format::Text => {
+            for result in geohashes {
+                let (_, cells) = result?;
+                for cell in cells {
+                    println!("{cell}");
+                }
+            }
+        }
+        Format::Json => {
+            #[derive(Serialize)]
+            struct GeohashCells {
+                geohash: String,
+                cells: Vec<crate::json::CellIndex>,
+            }
+
+            let geohashes = geohashes
+                .map(|result| {
+                    result.map(|(geohash, cells)| GeohashCells {
+                        geohash: geohash.to_string(),
+                        cells: cells.into_iter().map(Into::into).collect(),
+                    })
+                })
+                .collect::<AnyResult<Vec<_>>>()?;
+
+            crate::json::print(&geohashes, args.pretty)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the cells covering a geohash, sorted.
+fn geohash_to_cells(
+    geohash: Geohash,
+    args: &Args,
+) -> AnyResult<Vec<CellIndex>> {
+    let mut tiler = TilerBuilder::new(args.resolution)
+        .containment_mode(args.mode.into())
+        .build();
+    tiler
+        .add(geohash.bounds().to_polygon())
+        .with_context(|| format!("invalid bounds for geohash {geohash}"))?;
+
+    let mut cells = tiler.into_coverage().collect::<Vec<_>>();
+    cells.sort_unstable();
+    cells.dedup();
+
+    Ok(cells)
+}
@@ -1,8 +1,9 @@
 //! Expose [`ToCells::to_cells`]
 
-use anyhow::{Context, Result as AnyResult};
+use anyhow::{Context, Result as AnyResult, ensure};
 use clap::{Parser, ValueEnum};
 use geo_types::Geometry;
+use geojson::{GeoJson, JsonObject};
 use h3o::{
     LatLng, Resolution,
     geom::{ContainmentMode, PlotterBuilder, TilerBuilder},
@@ -10,10 +11,14 @@ use h3o::{
 use kml::{Kml, KmlReader};
 use std::{
     collections::HashSet,
-    io::{self, BufReader},
+    io::{BufReader, Read},
+    path::PathBuf,
 };
 
 /// Converts geometry from stdin into cells at the given resolution.
+///
+/// When a property is selected, each feature is tiled separately and every
+/// cell is printed along with the value of the property for that feature.
 #[derive(Parser, Debug)]
 pub struct Args {
     /// Target resolution.
@@ -21,68 +26,147 @@ pub struct Args {
     resolution: Resolution,
 
     /// Polyfill mode.
-    #[arg(short, long, value_enum, default_value_t = Mode::ContainsCentroid)]
-    mode: Mode,
+    #[arg(short, long, value_enum, default_value_t = crate::polyfill::Mode::ContainsCentroid)]
+    mode: crate::polyfill::Mode,
 
     /// Input format.
     #[arg(short, long, value_enum, default_value_t = Format::Geojson)]
     format: Format,
+
+    /// Input file (required for shapefile, read from stdin otherwise).
+    #[arg(short, long)]
+    input: Option<PathBuf>,
+
+    /// Feature property to print alongside each cell.
+    #[arg(long)]
+    property: Option<String>,
+
+    /// Print the cells crossed by each track, in order, along with the track
+    /// index, segment index and timestamp (GPX only).
+    #[arg(long, default_value_t = false)]
+    trace: bool,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
 enum Format {
     Geojson,
     Kml,
+    Shapefile,
+    Gpx,
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
-enum Mode {
-    ContainsCentroid,
-    ContainsBoundary,
-    IntersectsBoundary,
-    Covers,
-}
+/// Run the `geomToCells` command.
+pub fn run(args: &Args) -> AnyResult<()> {
+    if args.trace {
+        ensure!(args.format == Format::Gpx, "trace requires GPX input");
+        return print_trace(args);
+    }
+    let features = read_features(args)?;
 
-impl From<Mode> for ContainmentMode {
-    fn from(value: Mode) -> Self {
-        match value {
-            Mode::ContainsCentroid => Self::ContainsCentroid,
-            Mode::ContainsBoundary => Self::ContainsBoundary,
-            Mode::IntersectsBoundary => Self::IntersectsBoundary,
-            Mode::Covers => Self::Covers,
+    if let Some(ref name) = args.property {
+        for (geometry, properties) in features {
+            let value = properties
+                .get(name)
+                .with_context(|| format!("missing property {name}"))?;
+            let value = value
+                .as_str()
+                .map_or_else(|| value.to_string(), ToOwned::to_owned);
+            let indexes =
+                geometry_to_cells(geometry, args.resolution, args.mode.into())?;
+            for index in indexes {
+                println!("{index} {value}");
+            }
+        }
+    } else {
+        let mut indexes = HashSet::new();
+        for (geometry, _) in features {
+            indexes.extend(geometry_to_cells(
+                geometry,
+                args.resolution,
+                args.mode.into(),
+            )?);
+        }
+        for index in indexes {
+            println!("{index}");
         }
     }
+
+    Ok(())
 }
 
-/// Run the `cellToPolygon` command.
-pub fn run(args: &Args) -> AnyResult<()> {
-    let indexes = match args.format {
-        Format::Geojson => {
-            let geojson = geojson::GeoJson::from_reader(io::stdin())
-                .context("read GeoJSON")?;
-            let geometry =
-                Geometry::try_from(geojson).context("invalid geometry")?;
+/// Print the cells crossed by the GPX tracks.
+fn print_trace(args: &Args) -> AnyResult<()> {
+    let input = crate::io::open(args.input.as_deref())?;
+    let gpx = crate::gpx::read(input)?;
 
-            geometry_to_cells(geometry, args.resolution, args.mode.into())
+    for point in crate::gpx::trace(&gpx, args.resolution)? {
+        println!(
+            "{} {} {} {}",
+            point.cell,
+            point.track,
+            point.segment,
+            point.time.as_deref().unwrap_or("-")
+        );
+    }
+
+    Ok(())
+}
+
+/// Read the input features, as geometries and their properties.
+fn read_features(args: &Args) -> AnyResult<Vec<(Geometry, JsonObject)>> {
+    match args.format {
+        Format::Geojson => {
+            let input = crate::io::open(args.input.as_deref())?;
+            read_geojson(input)
         }
         Format::Kml => {
-            let kml: Kml<f64> =
-                KmlReader::from_reader(BufReader::new(io::stdin()))
-                    .read()
-                    .context("parse KML")?;
+            let input = crate::io::open(args.input.as_deref())?;
+            let kml: Kml<f64> = KmlReader::from_reader(BufReader::new(input))
+                .read()
+                .context("parse KML")?;
             let geometry = crate::kml::to_geometry(kml)
                 .context("invalid KML geometry")?
                 .context("no KML geometry")?;
 
-            geometry_to_cells(geometry, args.resolution, args.mode.into())
+            Ok(vec![(geometry, JsonObject::new())])
+        }
+        Format::Gpx => {
+            let input = crate::io::open(args.input.as_deref())?;
+            Ok(crate::gpx::to_features(crate::gpx::read(input)?))
+        }
+        Format::Shapefile => {
+            let path = args
+                .input
+                .as_deref()
+                .context("shapefile format requires an input file")?;
+            crate::shapefile::read(path).context("read shapefile")
         }
-    }?;
-
-    for index in indexes {
-        println!("{index}");
     }
+}
 
-    Ok(())
+/// Read features from a `GeoJSON` input.
+fn read_geojson(input: impl Read) -> AnyResult<Vec<(Geometry, JsonObject)>> {
+    let features = match GeoJson::from_reader(input).context("read GeoJSON")? {
+        GeoJson::FeatureCollection(collection) => collection.features,
+        GeoJson::Feature(feature) => vec![feature],
+        GeoJson::Geometry(geometry) => {
+            let geometry =
+                Geometry::try_from(geometry).context("invalid geometry")?;
+            return Ok(vec![(geometry, JsonObject::new())]);
+        }
+    };
+
+    features
+        .into_iter()
+        .filter_map(|feature| {
+            let properties = feature.properties.unwrap_or_default();
+            feature.geometry.map(|geometry| {
+                Geometry::try_from(geometry)
+                    .context("invalid geometry")
+                    .map(|geometry| (geometry, properties))
+            })
+        })
+        .collect()
 }
 
 fn geometry_to_cells(
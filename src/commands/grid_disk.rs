@@ -1,9 +1,11 @@
 //! Expose [`CellIndex::grid_disk`].
 
 use anyhow::{Context, Result as AnyResult};
+use arrow_array::{ArrayRef, RecordBatch, UInt32Array};
 use clap::{Parser, ValueEnum};
 use h3o::CellIndex;
 use serde::Serialize;
+use std::{path::PathBuf, sync::Arc};
 
 /// Print cell indexes `radius` distance away from the origin.
 ///
@@ -30,12 +32,18 @@ pub struct Args {
     /// Prettify the output (JSON only).
     #[arg(short, long, default_value_t = false)]
     pretty: bool,
+
+    /// Output file (Parquet and Arrow only, stdout otherwise).
+    #[arg(long)]
+    output: Option<PathBuf>,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
 enum Format {
     Text,
     Json,
+    Parquet,
+    Arrow,
 }
 
 /// Run the `gridDisk` command.
@@ -47,6 +55,12 @@ pub fn run(args: &Args) -> AnyResult<()> {
         Format::Json => {
             disks_to_json(indexes, args.radius, args.distance, args.pretty)
         }
+        Format::Parquet => {
+            disks_to_table(indexes, args, crate::table::Format::Parquet)
+        }
+        Format::Arrow => {
+            disks_to_table(indexes, args, crate::table::Format::Arrow)
+        }
     }
     .context("gridDisk")?;
 
@@ -125,3 +139,35 @@ fn disks_to_json(
         crate::json::print(&disks, pretty)
     }
 }
+
+/// Write disks as a `Parquet` or `Arrow` table, one row per neighbor.
+fn disks_to_table(
+    indexes: impl IntoIterator<Item = AnyResult<CellIndex>>,
+    args: &Args,
+    format: crate::table::Format,
+) -> AnyResult<()> {
+    let mut origins = Vec::new();
+    let mut cells = Vec::new();
+    let mut distances = Vec::new();
+    for origin in indexes {
+        let origin = origin?;
+        for (index, distance) in origin.grid_disk_distances_safe(args.radius) {
+            origins.push(origin);
+            cells.push(index);
+            distances.push(distance);
+        }
+    }
+
+    let mut columns = vec![
+        ("origin", crate::table::cells(origins)),
+        ("cell", crate::table::cells(cells)),
+    ];
+    if args.distance {
+        let distances: ArrayRef = Arc::new(UInt32Array::from(distances));
+        columns.push(("distance", distances));
+    }
+    let batch =
+        RecordBatch::try_from_iter(columns).context("build disk table")?;
+
+    crate::table::write(&batch, format, args.output.as_deref())
+}
@@ -1,8 +1,10 @@
 //! Expose [`LatLng::to_cell`]
 use anyhow::{Context, Result as AnyResult};
+use arrow_array::RecordBatch;
 use clap::{ArgGroup, Parser, ValueEnum};
 use either::Either;
-use h3o::{LatLng, Resolution};
+use h3o::{CellIndex, LatLng, Resolution};
+use std::path::PathBuf;
 
 /// Converts from lat/lng coordinates to cell indexes.
 ///
@@ -35,12 +37,18 @@ pub struct Args {
     /// Prettify the output (JSON only).
     #[arg(short, long, default_value_t = false)]
     pretty: bool,
+
+    /// Output file (Parquet and Arrow only, stdout otherwise).
+    #[arg(short, long)]
+    output: Option<PathBuf>,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
 enum Format {
     Text,
     Json,
+    Parquet,
+    Arrow,
 }
 
 /// Run the `latLngToCell` command.
@@ -52,23 +60,55 @@ pub fn run(args: &Args) -> AnyResult<()> {
     } else {
         Either::Right(crate::io::read_coords())
     }
-    .map(|input| input.map(|ll| ll.to_cell(args.resolution)));
+    .map(|input| input.map(|ll| (ll, ll.to_cell(args.resolution))));
 
     match args.format {
         Format::Text => {
-            for index in indexes {
-                println!("{}", index?);
+            for result in indexes {
+                println!("{}", result?.1);
             }
         }
         Format::Json => {
             let indexes = indexes
-                .map(|result| result.map(Into::into))
-                .collect::<AnyResult<Vec<crate::json::CellIndex>>>(
-            )?;
+                .map(|result| result.map(|(_, index)| index.into()))
+                .collect::<AnyResult<Vec<crate::json::CellIndex>>>()?;
 
             crate::json::print(&indexes, args.pretty)?;
         }
+        Format::Parquet => {
+            cells_to_table(indexes, args, crate::table::Format::Parquet)?;
+        }
+        Format::Arrow => {
+            cells_to_table(indexes, args, crate::table::Format::Arrow)?;
+        }
     }
 
     Ok(())
 }
+
+/// Write the coordinates and their cell as a `Parquet` or `Arrow` table.
+fn cells_to_table(
+    indexes: impl IntoIterator<Item = AnyResult<(LatLng, CellIndex)>>,
+    args: &Args,
+    format: crate::table::Format,
+) -> AnyResult<()> {
+    let (coords, indexes): (Vec<_>, Vec<_>) = indexes
+        .into_iter()
+        .collect::<AnyResult<Vec<_>>>()?
+        .into_iter()
+        .unzip();
+    let batch = RecordBatch::try_from_iter([
+        (
+            "lat",
+            crate::table::floats(coords.iter().copied().map(LatLng::lat)),
+        ),
+        (
+            "lng",
+            crate::table::floats(coords.iter().copied().map(LatLng::lng)),
+        ),
+        ("cell", crate::table::cells(indexes)),
+    ])
+    .context("build cell table")?;
+
+    crate::table::write(&batch, format, args.output.as_deref())
+}
@@ -1,18 +1,30 @@
 //! CLI subcommands implementation.
 
+pub mod aggregate;
+pub mod bin_points;
 pub mod cell_info;
 pub mod cell_to_boundary;
 pub mod cell_to_children;
+pub mod cell_to_geohashes;
 pub mod cell_to_latlng;
 pub mod cell_to_local_ij;
 pub mod cell_to_parent;
 pub mod cell_to_polygon;
+pub mod cell_to_tiles;
 pub mod compact;
 pub mod compress;
 pub mod decompress;
+pub mod geohash_to_cells;
 pub mod geom_to_cells;
 pub mod grid_disk;
 pub mod grid_path;
 pub mod index_decode;
 pub mod latlng_to_cell;
+pub mod normalize;
+pub mod preview;
+pub mod render;
 pub mod resolution_info;
+pub mod set_op;
+pub mod stats;
+pub mod tile_to_cells;
+pub mod tiles;
@@ -0,0 +1,123 @@
+//! Normalize sets of cells.
+
+use crate::cellset::CellSet;
+use anyhow::{Result as AnyResult, ensure};
+use clap::{Parser, ValueEnum};
+use h3o::CellIndex;
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt};
+
+/// Normalize the given set of indexes (from stdin).
+///
+/// Duplicates and cells covered by one of their ancestors are removed, and
+/// the remaining cells are sorted hierarchically (descendants first). Unlike
+/// `compact`, any mix of resolutions is accepted.
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// Only report the problems of the input, and fail if it isn't already
+    /// normalized.
+    #[arg(long, default_value_t = false)]
+    check: bool,
+
+    /// Output format.
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// Prettify the output (JSON only).
+    #[arg(short, long, default_value_t = false)]
+    pretty: bool,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+}
+
+/// Run the `normalize` command.
+pub fn run(args: &Args) -> AnyResult<()> {
+    let indexes =
+        crate::io::read_cell_indexes().collect::<AnyResult<Vec<_>>>()?;
+    let set = indexes.iter().copied().collect::<CellSet>();
+
+    if args.check {
+        let report = Report::new(&indexes, &set);
+        match args.format {
+            Format::Text => print!("{report}"),
+            Format::Json => crate::json::print(&report, args.pretty)?,
+        }
+        ensure!(report.is_normalized(), "input is not normalized");
+        return Ok(());
+    }
+
+    match args.format {
+        Format::Text => {
+            for index in set.iter() {
+                println!("{index}");
+            }
+        }
+        Format::Json => {
+            let normalized = set
+                .iter()
+                .map(Into::into)
+                .collect::<Vec<crate::json::CellIndex>>();
+            crate::json::print(&normalized, args.pretty)?;
+        }
+    }
+
+    Ok(())
+}
+
+// -----------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct Report {
+    /// Number of input cells.
+    cells: usize,
+    /// Number of repeated cells.
+    duplicates: usize,
+    /// Number of distinct cells covered by one of their ancestors.
+    overlaps: usize,
+    /// Whether the cells are in hierarchical order, without duplicates.
+    sorted: bool,
+    /// Number of input cells per resolution.
+    resolutions: BTreeMap<u8, usize>,
+}
+
+impl Report {
+    fn new(indexes: &[CellIndex], set: &CellSet) -> Self {
+        let mut distinct = indexes.to_vec();
+        distinct.sort_unstable();
+        distinct.dedup();
+
+        let mut resolutions = BTreeMap::new();
+        for index in indexes {
+            *resolutions.entry(u8::from(index.resolution())).or_default() += 1;
+        }
+
+        Self {
+            cells: indexes.len(),
+            duplicates: indexes.len() - distinct.len(),
+            overlaps: distinct.len() - set.len(),
+            sorted: indexes.is_sorted_by(|lhs, rhs| lhs < rhs),
+            resolutions,
+        }
+    }
+
+    const fn is_normalized(&self) -> bool {
+        self.duplicates == 0 && self.overlaps == 0 && self.sorted
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "cells:      {}", self.cells)?;
+        writeln!(f, "duplicates: {}", self.duplicates)?;
+        writeln!(f, "overlaps:   {}", self.overlaps)?;
+        writeln!(f, "sorted:     {}", self.sorted)?;
+        for (resolution, count) in &self.resolutions {
+            writeln!(f, "resolution {resolution:>2}: {count}")?;
+        }
+        Ok(())
+    }
+}
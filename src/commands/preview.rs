@@ -0,0 +1,276 @@
+//! Preview cells in the terminal.
+
+use anyhow::{Context, Result as AnyResult};
+use clap::{Parser, ValueEnum};
+use geo::MapCoords;
+use geo_types::Coord;
+use h3o::CellIndex;
+use std::{
+    env,
+    io::{self, Write},
+};
+
+/// Length of a degree of latitude, in kilometers.
+const KM_PER_DEGREE: f64 = 111.195;
+
+/// Previews indexes in the terminal.
+///
+/// The command reads H3 indexes (optionally followed by a value) from stdin
+/// and draws them with Unicode characters, fitted to the extent of the set.
+/// The extent and the scale of the drawing are printed below it.
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// Cell index.
+    #[arg(short, long)]
+    index: Option<CellIndex>,
+
+    /// Drawing mode.
+    #[arg(short, long, value_enum, default_value_t = Mode::HalfBlock)]
+    mode: Mode,
+
+    /// Width, in columns (terminal width if not set).
+    #[arg(long)]
+    width: Option<u16>,
+
+    /// Maximum height, in rows (terminal height if not set).
+    #[arg(long)]
+    height: Option<u16>,
+
+    /// Color cells by resolution instead of value.
+    #[arg(long, default_value_t = false)]
+    by_resolution: bool,
+
+    /// Disable colors.
+    #[arg(long, default_value_t = false)]
+    no_color: bool,
+
+    #[command(flatten)]
+    map: crate::render::MapArgs,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Mode {
+    /// Filled cells, two pixels per character (`▀`, `▄` and `█`).
+    HalfBlock,
+    /// Cell outlines, eight dots per character (`⣿`).
+    Braille,
+}
+
+impl Mode {
+    /// Returns the number of pixels per character, horizontally and
+    /// vertically.
+    const fn pixels(self) -> (usize, usize) {
+        match self {
+            Self::HalfBlock => (1, 2),
+            Self::Braille => (2, 4),
+        }
+    }
+}
+
+/// Run the `preview` command.
+pub fn run(args: &Args) -> AnyResult<()> {
+    let cells = crate::render::read_cells(args.index, args.map.value_field)?;
+    let (map, shapes, extent) =
+        crate::render::project(&cells, args.map.projection);
+    let extent = extent.context("no cell to preview")?;
+
+    let (columns, max_rows) = size(args);
+    let (dot_width, dot_height) = args.mode.pixels();
+    let width = columns * dot_width;
+    let height = usize::try_from(crate::render::Viewport::height_for(
+        extent,
+        u32::try_from(width).context("preview too wide")?,
+        0.,
+    ))
+    .context("preview too high")?
+    .min(max_rows * dot_height);
+    let rows = height.div_ceil(dot_height);
+    let viewport = crate::render::Viewport::fit(
+        extent,
+        f64::from(u32::try_from(width).context("preview too wide")?),
+        f64::from(
+            u32::try_from(rows * dot_height).context("preview too high")?,
+        ),
+        0.,
+    );
+
+    let colors = crate::render::ColorScale::new(&cells, args.map.ramp);
+    let mut canvas = crate::raster::Canvas::new(width, rows * dot_height);
+    for (&cell, shape) in cells.iter().zip(&shapes) {
+        let color = if args.by_resolution {
+            let resolution = u8::from(cell.index.resolution());
+            args.map.ramp.color(f64::from(resolution) / 15.)
+        } else {
+            colors.color(cell.value)
+        };
+        for polygon in shape {
+            let polygon = polygon.map_coords(|coord| viewport.pixel(coord));
+            match args.mode {
+                Mode::HalfBlock => canvas.fill(&polygon, color),
+                Mode::Braille => canvas.stroke(&polygon, color),
+            }
+        }
+    }
+
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    for row in 0..rows {
+        let line = match args.mode {
+            Mode::HalfBlock => half_block_row(&canvas, row, !args.no_color),
+            Mode::Braille => braille_row(&canvas, row, !args.no_color),
+        };
+        writeln!(out, "{}", line.trim_end()).context("write preview")?;
+    }
+    writeln!(
+        out,
+        "{}",
+        footer(cells.len(), extent, map, &viewport, dot_width)
+    )
+    .context("write footer")?;
+
+    Ok(())
+}
+
+/// Returns the width, in columns, and maximum height, in rows, of the preview.
+///
+/// Unless set from the options, the size comes from the `COLUMNS` and `LINES`
+/// environment variables, or defaults to 80x24. Rows are kept for the footer.
+fn size(args: &Args) -> (usize, usize) {
+    let from_env = |name, default| {
+        env::var(name)
+            .ok()
+            .and_then(|value| value.parse::<usize>().ok())
+            .filter(|&value| value > 0)
+            .unwrap_or(default)
+    };
+    let columns = args
+        .width
+        .map_or_else(|| from_env("COLUMNS", 80), usize::from);
+    let rows = args
+        .height
+        .map_or_else(|| from_env("LINES", 24).saturating_sub(2), usize::from);
+
+    (columns.max(1), rows.max(1))
+}
+
+/// Returns a row of half-block characters.
+///
+/// Each character shows two pixels: the upper one in the foreground, the lower
+/// one in the background.
+fn half_block_row(
+    canvas: &crate::raster::Canvas,
+    row: usize,
+    colored: bool,
+) -> String {
+    let mut line = String::new();
+    for x in 0..canvas.width() {
+        let (top, bottom) =
+            (canvas.get(x, 2 * row), canvas.get(x, 2 * row + 1));
+        let (symbol, foreground, background) = match (top, bottom) {
+            (None, None) => (' ', None, None),
+            (Some(top), None) => ('▀', Some(top), None),
+            (None, Some(bottom)) => ('▄', Some(bottom), None),
+            (Some(top), Some(bottom)) if top == bottom => {
+                ('█', Some(top), None)
+            }
+            (Some(top), Some(bottom)) => ('▀', Some(top), Some(bottom)),
+        };
+        push_char(
+            &mut line,
+            symbol,
+            foreground.filter(|_| colored),
+            background.filter(|_| colored),
+        );
+    }
+    line
+}
+
+/// Returns a row of braille characters.
+///
+/// Each character shows 2x4 pixels, drawn with the color of the first one.
+fn braille_row(
+    canvas: &crate::raster::Canvas,
+    row: usize,
+    colored: bool,
+) -> String {
+    // Bit of each dot, by row then column.
+    const DOTS: [[u32; 2]; 4] =
+        [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+    let mut line = String::new();
+    for column in 0..canvas.width().div_ceil(2) {
+        let mut bits = 0;
+        let mut color = None;
+        for (dy, dots) in DOTS.iter().enumerate() {
+            for (dx, bit) in dots.iter().enumerate() {
+                if let Some(pixel) = canvas.get(2 * column + dx, 4 * row + dy) {
+                    bits |= bit;
+                    color = color.or(Some(pixel));
+                }
+            }
+        }
+        let symbol = char::from_u32(0x2800 + bits).unwrap_or(' ');
+        let symbol = if bits == 0 { ' ' } else { symbol };
+        push_char(&mut line, symbol, color.filter(|_| colored), None);
+    }
+    line
+}
+
+/// Append a character, with its ANSI (24-bit) colors if any.
+fn push_char(
+    line: &mut String,
+    symbol: char,
+    foreground: Option<crate::render::Rgb>,
+    background: Option<crate::render::Rgb>,
+) {
+    if let Some(color) = foreground {
+        line.push_str(&ansi_color(38, color));
+    }
+    if let Some(color) = background {
+        line.push_str(&ansi_color(48, color));
+    }
+    line.push(symbol);
+    if foreground.is_some() || background.is_some() {
+        line.push_str("\x1b[0m");
+    }
+}
+
+/// Returns the escape sequence setting a foreground (38) or background (48)
+/// color.
+fn ansi_color(code: u8, color: crate::render::Rgb) -> String {
+    format!(
+        "\x1b[{code};2;{};{};{}m",
+        color.red, color.green, color.blue
+    )
+}
+
+/// Returns the footer, with the number of cells, the extent and the scale.
+///
+/// The scale is the width of a column at the center of the extent.
+fn footer(
+    count: usize,
+    extent: geo_types::Rect,
+    map: crate::render::Map,
+    viewport: &crate::render::Viewport,
+    dot_width: usize,
+) -> String {
+    let south_west = map.unproject(extent.min());
+    let north_east = map.unproject(extent.max());
+    let center = map.unproject(Coord {
+        x: extent.center().x,
+        y: extent.center().y,
+    });
+    let column = u32::try_from(dot_width).map_or(0., f64::from)
+        / viewport.scale()
+        * KM_PER_DEGREE
+        * center.y.to_radians().cos();
+    let scale = if column < 1. {
+        format!("{:.0} m", column * 1000.)
+    } else {
+        format!("{column:.1} km")
+    };
+
+    format!(
+        "cells: {count}, extent: {:.4},{:.4} → {:.4},{:.4} (lng,lat), scale: 1 column ≈ {scale}",
+        south_west.x, south_west.y, north_east.x, north_east.y,
+    )
+}
@@ -0,0 +1,112 @@
+//! Render cells as an image.
+
+use anyhow::{Context, Result as AnyResult};
+use clap::{Parser, ValueEnum};
+use h3o::CellIndex;
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+};
+
+/// Empty border around the cells, in pixels.
+const MARGIN: f64 = 10.;
+
+/// Renders indexes as an image.
+///
+/// The command reads H3 indexes (optionally followed by a value) from stdin
+/// and draws their boundaries, filled with colors from a color ramp. No
+/// basemap is drawn, so the rendering works offline.
+///
+/// SVG images keep one shape per cell, with its index and value as a tooltip.
+/// PNG images are rasterized, without outlines and with a transparent
+/// background, which scales to millions of cells.
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// Cell index.
+    #[arg(short, long)]
+    index: Option<CellIndex>,
+
+    /// Output format.
+    #[arg(short, long, value_enum, default_value_t = Format::Svg)]
+    format: Format,
+
+    /// Output file (stdout if not set).
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Image width, in pixels.
+    #[arg(long, default_value_t = 800)]
+    width: u32,
+
+    /// Image height, in pixels (computed from the cells' extent if not set).
+    #[arg(long)]
+    height: Option<u32>,
+
+    /// Label each cell with its index (SVG only).
+    #[arg(long, default_value_t = false)]
+    labels: bool,
+
+    /// Add a legend, with the range of values (SVG only).
+    #[arg(long, default_value_t = false)]
+    legend: bool,
+
+    #[command(flatten)]
+    map: crate::render::MapArgs,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Svg,
+    Png,
+}
+
+/// Run the `render` command.
+pub fn run(args: &Args) -> AnyResult<()> {
+    let cells = crate::render::read_cells(args.index, args.map.value_field)?;
+    let (map, shapes, extent) =
+        crate::render::project(&cells, args.map.projection);
+    let extent = extent.context("no cell to render")?;
+    let height = args.height.unwrap_or_else(|| {
+        crate::render::Viewport::height_for(extent, args.width, MARGIN)
+    });
+    let viewport = crate::render::Viewport::fit(
+        extent,
+        args.width.into(),
+        height.into(),
+        MARGIN,
+    );
+    let colors = crate::render::ColorScale::new(&cells, args.map.ramp);
+
+    let out: Box<dyn Write> = match args.output.as_deref() {
+        Some(path) => Box::new(crate::io::create(path)?),
+        None => Box::new(io::BufWriter::new(io::stdout().lock())),
+    };
+
+    match args.format {
+        Format::Svg => crate::svg::write(
+            out,
+            &cells,
+            &shapes,
+            map,
+            &colors,
+            &viewport,
+            crate::svg::Options {
+                width: args.width,
+                height,
+                labels: args.labels,
+                legend: args.legend,
+            },
+        ),
+        Format::Png => crate::png::write(
+            out,
+            &cells,
+            &shapes,
+            &colors,
+            &viewport,
+            (args.width, height),
+        ),
+    }
+    .context("render")?;
+
+    Ok(())
+}
@@ -0,0 +1,102 @@
+//! Set operations over cell coverages.
+
+use crate::cellset::CellSet;
+use anyhow::{Context, Result as AnyResult, ensure};
+use clap::{Parser, ValueEnum};
+use h3o::Resolution;
+use std::path::PathBuf;
+
+/// Computes a set operation over sets of indexes.
+///
+/// Each file holds one index per line, of any resolution (e.g. compacted
+/// cells). Sets are compared by the area they cover: a coarse cell in one set
+/// and its children in another one are the same area. The result is
+/// compacted, unless a resolution is requested.
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// Set operation.
+    #[arg(value_enum)]
+    operation: Operation,
+
+    /// Files of indexes (at least two).
+    #[arg(required = true, num_args = 2.., value_name = "FILE")]
+    files: Vec<PathBuf>,
+
+    /// Expand the result to this resolution instead of compacting it.
+    #[arg(short, long)]
+    resolution: Option<Resolution>,
+
+    /// Output format.
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// Prettify the output (JSON only).
+    #[arg(short, long, default_value_t = false)]
+    pretty: bool,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Operation {
+    /// Area covered by any set.
+    Union,
+    /// Area covered by every set.
+    Intersection,
+    /// Area covered by the first set but none of the others.
+    Difference,
+    /// Area covered by an odd number of sets (only one of two sets).
+    SymmetricDifference,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+}
+
+/// Run the `setOp` command.
+pub fn run(args: &Args) -> AnyResult<()> {
+    let mut sets = args
+        .files
+        .iter()
+        .map(|path| crate::io::read_cell_file(path).map(CellSet::from_iter));
+    let first = sets.next().context("no input file")??;
+    let mut result = sets.try_fold(first, |acc, set| {
+        let set = set?;
+        Ok::<_, anyhow::Error>(match args.operation {
+            Operation::Union => acc.union(&set),
+            Operation::Intersection => acc.intersection(&set),
+            Operation::Difference => acc.difference(&set),
+            Operation::SymmetricDifference => acc.symmetric_difference(&set),
+        })
+    })?;
+
+    let cells = if let Some(resolution) = args.resolution {
+        if let Some(finest) = result.finest_resolution() {
+            ensure!(
+                finest <= resolution,
+                "result has cells at resolution {finest}, finer than {resolution}"
+            );
+        }
+        result.uncompact(resolution).collect::<Vec<_>>()
+    } else {
+        result.compact(Resolution::Zero);
+        result.iter().collect()
+    };
+
+    match args.format {
+        Format::Text => {
+            for cell in cells {
+                println!("{cell}");
+            }
+        }
+        Format::Json => {
+            let cells = cells
+                .into_iter()
+                .map(Into::into)
+                .collect::<Vec<crate::json::CellIndex>>();
+            crate::json::print(&cells, args.pretty)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,229 @@
+//! Summarize a set of cells.
+
+use crate::cellset::CellSet;
+use anyhow::Result as AnyResult;
+use clap::{Parser, ValueEnum};
+use geo::BoundingRect;
+use h3o::{CellIndex, LatLng, Resolution};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
+
+/// Print statistics on the given set of indexes (from stdin).
+///
+/// Overlapping indexes are counted once: duplicates and cells covered by one of
+/// their ancestors are dropped, and reported as overlaps. Areas are in km², the
+/// bounding box is given as west, south, east and north (crossing the
+/// antimeridian if the western longitude is greater than the eastern one), and
+/// the centroid as lat/lng. Cells are connected when they share an edge,
+/// checked at the resolution of each cell.
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// Output format.
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// Prettify the output (JSON only).
+    #[arg(short, long, default_value_t = false)]
+    pretty: bool,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+}
+
+/// Run the `stats` command.
+pub fn run(args: &Args) -> AnyResult<()> {
+    let mut count = 0;
+    let set = crate::io::read_cell_indexes()
+        .inspect(|_| count += 1)
+        .collect::<AnyResult<CellSet>>()?;
+    let indexes = set.iter().collect::<Vec<_>>();
+
+    let stats = Stats::new(&indexes, count - indexes.len());
+    match args.format {
+        Format::Text => print!("{stats}"),
+        Format::Json => crate::json::print(&stats, args.pretty)?,
+    }
+
+    Ok(())
+}
+
+// -----------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Stats {
+    cells: usize,
+    overlaps: usize,
+    pentagons: usize,
+    components: usize,
+    area: Option<AreaStats>,
+    bbox: Option<Vec<f64>>,
+    centroid: Option<(f64, f64)>,
+    resolutions: BTreeMap<u8, usize>,
+    base_cells: BTreeMap<u8, usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct AreaStats {
+    total: f64,
+    min: f64,
+    max: f64,
+    mean: f64,
+}
+
+impl Stats {
+    /// Returns the statistics of a set of disjoint cells, out of which
+    /// `overlaps` overlapping ones have been dropped.
+    fn new(value: &[CellIndex], overlaps: usize) -> Self {
+        let mut resolutions = BTreeMap::new();
+        let mut base_cells = BTreeMap::new();
+        for index in value {
+            *resolutions.entry(u8::from(index.resolution())).or_default() += 1;
+            *base_cells.entry(u8::from(index.base_cell())).or_default() += 1;
+        }
+        let areas = value
+            .iter()
+            .map(|index| index.area_km2())
+            .collect::<Vec<_>>();
+
+        Self {
+            cells: value.len(),
+            overlaps,
+            pentagons: value.iter().filter(|index| index.is_pentagon()).count(),
+            components: components(value),
+            area: area_stats(&areas),
+            bbox: crate::geojson::extent(
+                value
+                    .iter()
+                    .flat_map(|&index| crate::antimeridian::cell(index))
+                    .filter_map(|polygon| polygon.bounding_rect())
+                    .map(|rect| {
+                        (rect.min().x, rect.min().y, rect.max().x, rect.max().y)
+                    }),
+            ),
+            centroid: centroid(value, &areas),
+            resolutions,
+            base_cells,
+        }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "cells:        {}", self.cells)?;
+        writeln!(f, "overlaps:     {}", self.overlaps)?;
+        writeln!(f, "pentagons:    {}", self.pentagons)?;
+        writeln!(f, "components:   {}", self.components)?;
+        if let Some(ref area) = self.area {
+            writeln!(f, "area (km2):   {:.6}", area.total)?;
+            writeln!(
+                f,
+                "cell area:    min {:.6}, max {:.6}, mean {:.6}",
+                area.min, area.max, area.mean
+            )?;
+        }
+        if let Some(ref bbox) = self.bbox {
+            let bbox = bbox
+                .iter()
+                .map(|value| format!("{value:.9}"))
+                .collect::<Vec<_>>();
+            writeln!(f, "bbox:         {}", bbox.join(" "))?;
+        }
+        if let Some((lat, lng)) = self.centroid {
+            writeln!(f, "centroid:     {lat:.9} {lng:.9}")?;
+        }
+        for (resolution, count) in &self.resolutions {
+            writeln!(f, "resolution {resolution:>2}: {count}")?;
+        }
+        for (base_cell, count) in &self.base_cells {
+            writeln!(f, "base cell {base_cell:>3}: {count}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the total, min, max and mean of the areas, if any.
+#[expect(clippy::cast_precision_loss, reason = "count is small enough")]
+fn area_stats(areas: &[f64]) -> Option<AreaStats> {
+    let min = areas.iter().copied().reduce(f64::min)?;
+    let max = areas.iter().copied().reduce(f64::max)?;
+    let total = areas.iter().sum::<f64>();
+
+    Some(AreaStats {
+        total,
+        min,
+        max,
+        mean: total / areas.len() as f64,
+    })
+}
+
+/// Returns the area-weighted centroid of the cells, as lat/lng.
+///
+/// Cell centers are averaged as 3D unit vectors, which handles the
+/// antimeridian and the poles.
+fn centroid(indexes: &[CellIndex], areas: &[f64]) -> Option<(f64, f64)> {
+    let (x, y, z) = indexes.iter().zip(areas).fold(
+        (0., 0., 0.),
+        |(x, y, z), (&index, &area)| {
+            let center = LatLng::from(index);
+            let (lat, lng) = (center.lat_radians(), center.lng_radians());
+            (
+                (area * lat.cos()).mul_add(lng.cos(), x),
+                (area * lat.cos()).mul_add(lng.sin(), y),
+                area.mul_add(lat.sin(), z),
+            )
+        },
+    );
+    // Antipodal cells may cancel each other out.
+    let norm = x.hypot(y).hypot(z);
+    (norm > 1e-12 * areas.iter().sum::<f64>())
+        .then(|| (z.atan2(x.hypot(y)).to_degrees(), y.atan2(x).to_degrees()))
+}
+
+/// Returns the number of groups of connected cells.
+///
+/// A cell is connected to the cells of the set covering its neighbors at its
+/// resolution, and to its ancestors in the set.
+fn components(indexes: &[CellIndex]) -> usize {
+    let positions = indexes
+        .iter()
+        .enumerate()
+        .map(|(position, &index)| (index, position))
+        .collect::<HashMap<_, _>>();
+    // Returns the position of the coarsest cell of the set covering a cell.
+    let covering = |index: CellIndex| {
+        Resolution::range(Resolution::Zero, index.resolution()).find_map(
+            |resolution| positions.get(&index.parent(resolution)?).copied(),
+        )
+    };
+
+    // Union-find, with path halving.
+    let mut roots = (0..indexes.len()).collect::<Vec<_>>();
+    let find = |roots: &mut [usize], mut position: usize| {
+        while roots[position] != position {
+            roots[position] = roots[roots[position]];
+            position = roots[position];
+        }
+        position
+    };
+    for (position, &index) in indexes.iter().enumerate() {
+        // The disk includes the cell itself, which links it to its ancestors.
+        for neighbor in index.grid_disk_safe(1) {
+            if let Some(other) = covering(neighbor) {
+                let (lhs, rhs) =
+                    (find(&mut roots, position), find(&mut roots, other));
+                roots[lhs] = rhs;
+            }
+        }
+    }
+
+    (0..indexes.len())
+        .filter(|&position| find(&mut roots, position) == position)
+        .count()
+}
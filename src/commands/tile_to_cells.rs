@@ -0,0 +1,111 @@
+//! Expose [`TilerBuilder`] over Web Mercator tiles.
+
+use crate::tile::Tile;
+use anyhow::{Context, Result as AnyResult};
+use clap::{Parser, ValueEnum};
+use h3o::{CellIndex, Resolution, geom::TilerBuilder};
+use serde::Serialize;
+
+/// Zoom level of the widest tiles the tiler can take as is.
+///
+/// The tiler assumes edges spanning 180° or more cross the antimeridian, so
+/// wider tiles are split into their descendants at this zoom level.
+const MIN_TILER_ZOOM: u8 = 2;
+
+/// Converts Web Mercator tiles into the cells covering them.
+///
+/// Tiles are given as `z/x/y` coordinates (XYZ scheme) or as quadkeys. Web
+/// Mercator stops at ±85.05° of latitude: unless `--include-poles` is set,
+/// the polar areas beyond are not covered by any tile.
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// Tile, as `z/x/y` or quadkey.
+    #[arg(short, long)]
+    tile: Option<Tile>,
+
+    /// Target resolution.
+    #[arg(short, long)]
+    resolution: Resolution,
+
+    /// Polyfill mode.
+    #[arg(short, long, value_enum, default_value_t = crate::polyfill::Mode::ContainsCentroid)]
+    mode: crate::polyfill::Mode,
+
+    /// Extend the first and last rows of tiles to the poles.
+    #[arg(long, default_value_t = false)]
+    include_poles: bool,
+
+    /// Output format.
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// Prettify the output (JSON only).
+    #[arg(short, long, default_value_t = false)]
+    pretty: bool,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+}
+
+/// Run the `tileToCells` command.
+pub fn run(args: &Args) -> AnyResult<()> {
+    let tiles = crate::utils::get_tiles(args.tile).map(|tile| {
+        tile.and_then(|tile| Ok((tile, tile_to_cells(tile, args)?)))
+    });
+
+    match args.format {
+        Format::Text => {
+            for result in tiles {
+                let (_, cells) = result?;
+                for cell in cells {
+                    println!("{cell}");
+                }
+            }
+        }
+        Format::Json => {
+            #[derive(Serialize)]
+            struct TileCells {
+                tile: String,
+                cells: Vec<crate::json::CellIndex>,
+            }
+
+            let tiles = tiles
+                .map(|result| {
+                    result.map(|(tile, cells)| TileCells {
+                        tile: tile.to_string(),
+                        cells: cells.into_iter().map(Into::into).collect(),
+                    })
+                })
+                .collect::<AnyResult<Vec<_>>>()?;
+
+            crate::json::print(&tiles, args.pretty)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the cells covering a tile, sorted.
+fn tile_to_cells(tile: Tile, args: &Args) -> AnyResult<Vec<CellIndex>> {
+    let mut tiler = TilerBuilder::new(args.resolution)
+        .containment_mode(args.mode.into())
+        .build();
+    tiler
+        .add_batch(tile.descendants(MIN_TILER_ZOOM).map(|tile| {
+            if args.include_poles {
+                tile.extended_bounds().to_polygon()
+            } else {
+                tile.bounds().to_polygon()
+            }
+        }))
+        .with_context(|| format!("invalid bounds for tile {tile}"))?;
+
+    let mut cells = tiler.into_coverage().collect::<Vec<_>>();
+    cells.sort_unstable();
+    cells.dedup();
+
+    Ok(cells)
+}
@@ -0,0 +1,355 @@
+//! Generate vector tiles from cells.
+
+use anyhow::{Context, Result as AnyResult, ensure};
+use clap::{Parser, ValueEnum};
+use geo::{BoundingRect, MapCoords, Translate};
+use geo_types::{Coord, MultiPolygon, Rect};
+use h3o::{CellIndex, Resolution, geom::SolventBuilder};
+use std::{
+    collections::BTreeMap, num::NonZeroUsize, ops::RangeInclusive,
+    path::PathBuf,
+};
+
+/// Highest zoom level of the generated tiles, below `crate::tile::MAX_ZOOM`.
+///
+/// Cells reach resolution 15 around zoom 21 (see `resolution_for`): deeper
+/// levels only magnify the same cells, with four times as many tiles each, so
+/// a few levels of overzoom are enough.
+const MAX_TILES_ZOOM: u8 = 24;
+
+/// Length of the equator in Web Mercator, in meters.
+const EQUATOR_M: f64 = 40_075_016.685_578_49;
+
+/// Size of a tile on screen, in pixels.
+const TILE_SIZE: f64 = 256.;
+
+/// Minimum width of the cells drawn at a zoom level, in pixels.
+const MIN_CELL_SIZE: f64 = 8.;
+
+/// Generates vector tiles from indexes.
+///
+/// The command reads H3 indexes (optionally followed by a value) from stdin
+/// and writes them as Mapbox Vector Tiles over a range of zoom levels, either
+/// as a `{z}/{x}/{y}.mvt` directory or as a single `MBTiles`/`PMTiles`
+/// archive.
+///
+/// At each zoom level, cells narrower than a few pixels are either replaced by
+/// their ancestor at a fitting resolution, or dissolved into polygons.
+/// Features have the number of input cells they cover (`count`), their mean
+/// value (`value`, if any) and, unless dissolved, their index (`h3`, also used
+/// as the feature ID) as properties.
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// Cell index.
+    #[arg(short, long)]
+    index: Option<CellIndex>,
+
+    /// Output format.
+    #[arg(short, long, value_enum, default_value_t = Format::Directory)]
+    format: Format,
+
+    /// Output directory or archive.
+    #[arg(short, long)]
+    output: PathBuf,
+
+    /// Minimum zoom level.
+    #[arg(long, default_value_t = 0, value_parser = clap::value_parser!(u8).range(0..=i64::from(MAX_TILES_ZOOM)))]
+    min_zoom: u8,
+
+    /// Maximum zoom level.
+    #[arg(long, default_value_t = 14, value_parser = clap::value_parser!(u8).range(0..=i64::from(MAX_TILES_ZOOM)))]
+    max_zoom: u8,
+
+    /// How cells too small for a zoom level are drawn.
+    #[arg(long, value_enum, default_value_t = Coarsening::Parent)]
+    coarsen: Coarsening,
+
+    /// Layer name.
+    #[arg(long, default_value = "cells")]
+    layer: String,
+
+    /// Input field holding the value of each cell, counted from 1 after the
+    /// cell index.
+    #[arg(long)]
+    value_field: Option<NonZeroUsize>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// One file per tile.
+    Directory,
+    Mbtiles,
+    Pmtiles,
+}
+
+/// How cells too small for a zoom level are drawn.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Coarsening {
+    /// Replace the cells by their ancestor, with their mean value.
+    Parent,
+    /// Dissolve the cells into polygons, grouped by value.
+    Dissolve,
+}
+
+/// A feature of the layer.
+struct Feature {
+    /// Shape of the feature.
+    shape: Shape,
+    /// Number of input cells covered by the feature.
+    count: usize,
+    /// Mean value of the input cells, if any.
+    value: Option<f64>,
+}
+
+/// Shape of a feature.
+enum Shape {
+    /// A single cell.
+    Cell(CellIndex),
+    /// Dissolved cells, split along the antimeridian.
+    Polygons(MultiPolygon),
+}
+
+/// Run the `tiles` command.
+pub fn run(args: &Args) -> AnyResult<()> {
+    ensure!(
+        args.min_zoom <= args.max_zoom,
+        "min zoom ({}) is greater than max zoom ({})",
+        args.min_zoom,
+        args.max_zoom
+    );
+    let cells = crate::render::read_cells(args.index, args.value_field)?;
+    let bounds = cells
+        .iter()
+        .flat_map(|cell| crate::antimeridian::cell(cell.index))
+        .filter_map(|polygon| polygon.bounding_rect())
+        .reduce(|acc, rect| {
+            Rect::new(
+                (acc.min().x.min(rect.min().x), acc.min().y.min(rect.min().y)),
+                (acc.max().x.max(rect.max().x), acc.max().y.max(rect.max().y)),
+            )
+        })
+        .context("no cell to tile")?;
+
+    let mut fields = vec![("count", "Number")];
+    if args.value_field.is_some() {
+        fields.push(("value", "Number"));
+    }
+    // Dissolved cells have no index.
+    let finest = resolution_for(args.max_zoom);
+    if args.coarsen == Coarsening::Parent
+        || cells.iter().any(|cell| cell.index.resolution() <= finest)
+    {
+        fields.insert(0, ("h3", "String"));
+    }
+    let mut tileset = crate::tileset::Tileset {
+        layer: args.layer.clone(),
+        fields,
+        min_zoom: args.min_zoom,
+        max_zoom: args.max_zoom,
+        bounds,
+        tiles: BTreeMap::new(),
+    };
+
+    // Dissolved cells, along with the number of cells they come from.
+    let mut dissolved = (0, Vec::new());
+    for zoom in args.min_zoom..=args.max_zoom {
+        let resolution = resolution_for(zoom);
+        let features = match args.coarsen {
+            Coarsening::Parent => coarsen(cells.iter(), resolution),
+            Coarsening::Dissolve => {
+                let (coarse, fine) =
+                    cells.iter().partition::<Vec<_>, _>(|cell| {
+                        cell.index.resolution() <= resolution
+                    });
+                // Finer cells only get fewer as the zoom level increases.
+                if fine.len() != dissolved.0 {
+                    dissolved = (fine.len(), dissolve(fine)?);
+                }
+                coarsen(coarse, resolution)
+            }
+        };
+
+        let mut layers = BTreeMap::new();
+        for feature in features.iter().chain(&dissolved.1) {
+            add_feature(&mut layers, feature, zoom, &args.layer)
+                .context("encode feature")?;
+        }
+        tileset
+            .tiles
+            .extend(layers.into_iter().filter_map(|(tile, layer)| {
+                (!layer.is_empty()).then(|| (tile, layer.encode()))
+            }));
+    }
+
+    crate::tileset::write(
+        &tileset,
+        match args.format {
+            Format::Directory => crate::tileset::Format::Directory,
+            Format::Mbtiles => crate::tileset::Format::Mbtiles,
+            Format::Pmtiles => crate::tileset::Format::Pmtiles,
+        },
+        &args.output,
+    )
+    .context("write tiles")
+}
+
+/// Returns the finest resolution whose cells are wide enough to be drawn at
+/// the given zoom level.
+fn resolution_for(zoom: u8) -> Resolution {
+    let pixel = EQUATOR_M / (TILE_SIZE * f64::from(1_u32 << zoom));
+    Resolution::range(Resolution::Zero, Resolution::Fifteen)
+        .rev()
+        .find(|resolution| {
+            2. * resolution.edge_length_m() >= MIN_CELL_SIZE * pixel
+        })
+        .unwrap_or(Resolution::Zero)
+}
+
+/// Replace the cells finer than the resolution by their ancestor.
+///
+/// Cells sharing the same ancestor are merged into a single feature, with
+/// their mean value.
+fn coarsen<'a>(
+    cells: impl IntoIterator<Item = &'a crate::render::Cell>,
+    resolution: Resolution,
+) -> Vec<Feature> {
+    // Number of cells, sum and number of values, by ancestor.
+    let mut parents = BTreeMap::<_, (usize, f64, usize)>::new();
+    for cell in cells {
+        let parent = cell.index.parent(resolution).unwrap_or(cell.index);
+        let entry = parents.entry(parent).or_default();
+        entry.0 += 1;
+        if let Some(value) = cell.value {
+            entry.1 += value;
+            entry.2 += 1;
+        }
+    }
+
+    parents
+        .into_iter()
+        .map(|(index, (count, sum, values))| Feature {
+            shape: Shape::Cell(index),
+            count,
+            value: mean(sum, values),
+        })
+        .collect()
+}
+
+/// Dissolve the cells into polygons, one feature per distinct value.
+fn dissolve(cells: Vec<&crate::render::Cell>) -> AnyResult<Vec<Feature>> {
+    let mut groups = BTreeMap::<_, Vec<_>>::new();
+    for cell in cells {
+        groups
+            .entry(cell.value.map(f64::to_bits))
+            .or_default()
+            .push(cell.index);
+    }
+
+    let solvent = SolventBuilder::new().build();
+    groups
+        .into_iter()
+        .map(|(value, cells)| {
+            let count = cells.len();
+            let cells = crate::utils::uncompact_to_finest(cells)?;
+            let polygons =
+                solvent.dissolve(cells).context("compute polygons")?;
+            Ok(Feature {
+                shape: Shape::Polygons(crate::antimeridian::multi_polygon(
+                    polygons,
+                    crate::antimeridian::Mode::Split,
+                )),
+                count,
+                value: value.map(f64::from_bits),
+            })
+        })
+        .collect()
+}
+
+/// Add a feature to the layers of the tiles it intersects.
+fn add_feature(
+    layers: &mut BTreeMap<crate::tile::Tile, crate::mvt::Layer>,
+    feature: &Feature,
+    zoom: u8,
+    name: &str,
+) -> AnyResult<()> {
+    // Size of the world at this zoom level, in tile coordinates.
+    let size = f64::from(crate::mvt::EXTENT) * f64::from(1_u32 << zoom);
+    let to_world = |coord| {
+        let coord = crate::tile::mercator(coord);
+        Coord {
+            x: coord.x * size,
+            y: coord.y * size,
+        }
+    };
+    let polygons = crate::mvt::quantize(&match feature.shape {
+        Shape::Cell(index) => {
+            crate::antimeridian::cell(index).map_coords(to_world)
+        }
+        Shape::Polygons(ref polygons) => polygons.map_coords(to_world),
+    });
+    let Some(rect) = polygons.bounding_rect() else {
+        return Ok(());
+    };
+
+    let extent = f64::from(crate::mvt::EXTENT);
+    for x in tile_range(rect.min().x, rect.max().x, zoom) {
+        for y in tile_range(rect.min().y, rect.max().y, zoom) {
+            let tile = crate::tile::Tile { zoom, x, y };
+            let mut properties = Vec::with_capacity(3);
+            if let Shape::Cell(index) = feature.shape {
+                properties
+                    .push(("h3", crate::mvt::Value::Text(index.to_string())));
+            }
+            properties.push((
+                "count",
+                crate::mvt::Value::Integer(feature.count as u64),
+            ));
+            if let Some(value) = feature.value {
+                properties.push(("value", crate::mvt::Value::Number(value)));
+            }
+
+            layers
+                .entry(tile)
+                .or_insert_with(|| crate::mvt::Layer::new(name))
+                .add(
+                    match feature.shape {
+                        Shape::Cell(index) => Some(u64::from(index)),
+                        Shape::Polygons(_) => None,
+                    },
+                    properties,
+                    &polygons.translate(
+                        -f64::from(x) * extent,
+                        -f64::from(y) * extent,
+                    ),
+                )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the range of tiles covering the world coordinates, margin
+/// included.
+#[expect(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    reason = "tile numbers are clamped to the zoom level's range"
+)]
+fn tile_range(min: f64, max: f64, zoom: u8) -> RangeInclusive<u32> {
+    let extent = f64::from(crate::mvt::EXTENT);
+    let last = f64::from(crate::tile::Tile::count(zoom) - 1);
+    let first = ((min - crate::mvt::BUFFER) / extent)
+        .floor()
+        .clamp(0., last);
+    let end = ((max + crate::mvt::BUFFER) / extent)
+        .floor()
+        .clamp(0., last);
+
+    (first as u32)..=(end as u32)
+}
+
+/// Returns the mean of the values, if any.
+#[expect(clippy::cast_precision_loss, reason = "count is small enough")]
+fn mean(sum: f64, count: usize) -> Option<f64> {
+    (count > 0).then(|| sum / count as f64)
+}
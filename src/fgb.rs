@@ -0,0 +1,60 @@
+use crate::layer::{ColumnKind, Layer, Value};
+use anyhow::{Context, Result as AnyResult};
+use flatgeobuf::{
+    ColumnType, FgbCrs, FgbWriter, FgbWriterOptions, GeometryType,
+};
+use geozero::{ColumnValue, PropertyProcessor};
+use std::io::Write;
+
+/// Write the layer as an indexed `FlatGeobuf` dataset.
+pub fn write(out: impl Write, layer: Layer) -> AnyResult<()> {
+    let Layer {
+        name,
+        columns,
+        features,
+    } = layer;
+    let options = FgbWriterOptions {
+        crs: FgbCrs {
+            code: 4326,
+            ..FgbCrs::default()
+        },
+        ..FgbWriterOptions::default()
+    };
+    let mut writer =
+        FgbWriter::create_with_options(&name, GeometryType::Unknown, options)
+            .context("create FlatGeobuf writer")?;
+
+    for column in &columns {
+        let kind = match column.kind {
+            ColumnKind::Integer => ColumnType::Long,
+            ColumnKind::Text => ColumnType::String,
+        };
+        writer.add_column(&column.name, kind, |_, _| {});
+    }
+
+    for feature in features {
+        let mut result = Ok(());
+        writer
+            .add_feature_geom(feature.geometry, |writer| {
+                result = feature
+                    .values
+                    .iter()
+                    .zip(&columns)
+                    .enumerate()
+                    .try_for_each(|(i, (value, column))| {
+                        let value = match *value {
+                            Value::Integer(value) => ColumnValue::Long(value),
+                            Value::Text(ref value) => {
+                                ColumnValue::String(value)
+                            }
+                            Value::Null => return Ok(()),
+                        };
+                        writer.property(i, &column.name, &value).map(|_| ())
+                    });
+            })
+            .context("write FlatGeobuf feature")?;
+        result.context("write FlatGeobuf properties")?;
+    }
+
+    writer.write(out).context("write FlatGeobuf")
+}
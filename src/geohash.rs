@@ -0,0 +1,139 @@
+use anyhow::{Context, Error as AnyError, ensure};
+use geo_types::{Coord, Rect};
+use std::{fmt, str::FromStr};
+
+/// Characters of the geohash base32 encoding.
+const ALPHABET: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// Highest supported precision (60 bits).
+pub const MAX_PRECISION: u8 = 12;
+
+/// A geohash, as the column and row of its box at its precision.
+///
+/// Columns go from west to east and rows from south to north.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Geohash {
+    pub precision: u8,
+    pub x: u32,
+    pub y: u32,
+}
+
+impl Geohash {
+    /// Returns the number of columns and rows at a precision.
+    ///
+    /// Bits alternate between longitude and latitude, starting with the
+    /// longitude, so odd precisions have twice as many columns as rows.
+    pub const fn count(precision: u8) -> (u32, u32) {
+        let bits = 5 * precision as u32;
+        (1 << bits.div_ceil(2), 1 << (bits / 2))
+    }
+
+    /// Returns the geohash containing a longitude/latitude coordinate.
+    ///
+    /// Coordinates on the eastern or northern edge of the world fall in the
+    /// last column or row.
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "columns and rows are clamped to the precision's range"
+    )]
+    pub fn containing(coord: Coord, precision: u8) -> Self {
+        let (columns, rows) = Self::count(precision);
+        let number = |ratio: f64, count: u32| {
+            let count = f64::from(count);
+            (ratio * count).floor().clamp(0., count - 1.) as u32
+        };
+
+        Self {
+            precision,
+            x: number((coord.x + 180.) / 360., columns),
+            y: number((coord.y + 90.) / 180., rows),
+        }
+    }
+
+    /// Returns the bounds of the geohash, in longitude/latitude.
+    pub fn bounds(self) -> Rect {
+        let (columns, rows) = Self::count(self.precision);
+        let (width, height) =
+            (360. / f64::from(columns), 180. / f64::from(rows));
+        let (x, y) = (f64::from(self.x), f64::from(self.y));
+
+        Rect::new(
+            Coord {
+                x: x.mul_add(width, -180.),
+                y: y.mul_add(height, -90.),
+            },
+            Coord {
+                x: (x + 1.).mul_add(width, -180.),
+                y: (y + 1.).mul_add(height, -90.),
+            },
+        )
+    }
+
+    /// Returns the bits of the geohash, interleaved from the longitude.
+    fn bits(self) -> u64 {
+        let bits = 5 * u32::from(self.precision);
+        (0..bits).fold(0, |acc, i| {
+            // Bits are read from the most significant one.
+            let (value, bit) = if i % 2 == 0 {
+                (self.x, bits.div_ceil(2) - 1 - i / 2)
+            } else {
+                (self.y, bits / 2 - 1 - i / 2)
+            };
+            (acc << 1) | u64::from((value >> bit) & 1)
+        })
+    }
+}
+
+impl FromStr for Geohash {
+    type Err = AnyError;
+
+    /// Parse a geohash from its base32 encoding (case-insensitive).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let precision = u8::try_from(s.len()).context("geohash too long")?;
+        ensure!(precision > 0, "empty geohash");
+        ensure!(
+            precision <= MAX_PRECISION,
+            "geohash longer than {MAX_PRECISION} characters"
+        );
+
+        let mut geohash = Self {
+            precision,
+            x: 0,
+            y: 0,
+        };
+        let mut is_lng = true;
+        for symbol in s.bytes().map(|byte| byte.to_ascii_lowercase()) {
+            let value = ALPHABET
+                .iter()
+                .position(|&candidate| candidate == symbol)
+                .with_context(|| {
+                    format!("invalid geohash character {}", char::from(symbol))
+                })?;
+            for bit in (0..5).rev() {
+                let bit = u32::from((value >> bit) & 1 == 1);
+                if is_lng {
+                    geohash.x = (geohash.x << 1) | bit;
+                } else {
+                    geohash.y = (geohash.y << 1) | bit;
+                }
+                is_lng = !is_lng;
+            }
+        }
+
+        Ok(geohash)
+    }
+}
+
+impl fmt::Display for Geohash {
+    /// Format the geohash in base32.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bits = self.bits();
+        for i in (0..self.precision).rev() {
+            let value =
+                usize::try_from((bits >> (5 * i)) & 0x1f).expect("5-bit value");
+            write!(f, "{}", char::from(ALPHABET[value]))?;
+        }
+        Ok(())
+    }
+}
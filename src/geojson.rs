@@ -1,24 +1,74 @@
-use geo_types::{LineString, coord};
-use geojson::{Feature, JsonObject, JsonValue};
+use clap::ValueEnum;
+use geo::BoundingRect;
+use geo_types::{Geometry, LineString, Rect, coord};
+use geojson::{
+    Bbox, Feature, FeatureCollection, GeoJson, JsonObject, JsonValue,
+    feature::Id,
+};
 use h3o::{CellIndex, LatLng};
 
+/// `GeoJSON` feature options.
+#[derive(clap::Args, Debug, Copy, Clone)]
+#[command(next_help_heading = "GeoJSON options")]
+pub struct FeatureArgs {
+    /// Add the cell attributes (resolution, base cell, area, …) to the
+    /// features' properties.
+    #[arg(long, default_value_t = false)]
+    pub attributes: bool,
+
+    /// Type of the features' ID.
+    ///
+    /// Cell indexes exceed 2^53, so numeric IDs are corrupted by clients that
+    /// parse JSON numbers as doubles (e.g. JavaScript).
+    #[arg(long, value_enum, default_value_t = IdKind::String)]
+    pub id: IdKind,
+
+    /// Add a bounding box to every feature and to the collection.
+    #[arg(long, default_value_t = false)]
+    pub bbox: bool,
+}
+
+/// Type of a cell feature ID.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum IdKind {
+    /// Cell index as an hexadecimal string.
+    String,
+    /// Cell index as an integer.
+    Number,
+    /// No ID.
+    None,
+}
+
+impl IdKind {
+    /// Returns the feature ID of the given cell.
+    fn id(self, index: CellIndex) -> Option<Id> {
+        match self {
+            Self::String => Some(Id::String(index.to_string())),
+            Self::Number => Some(Id::Number(u64::from(index).into())),
+            Self::None => None,
+        }
+    }
+}
+
 /// Returns `GeoJSON` features representing the indexes' boundaries.
-pub fn boundaries(indexes: &[CellIndex]) -> Vec<Feature> {
+///
+/// Boundaries crossing the antimeridian are fixed according to `antimeridian`.
+pub fn boundaries(
+    indexes: &[CellIndex],
+    options: FeatureArgs,
+    antimeridian: crate::antimeridian::Mode,
+) -> Vec<Feature> {
     indexes
         .iter()
         .map(|index| {
             let mut linestring: LineString = index.boundary().into();
             linestring.close();
-            let geometry = geojson::Geometry::new((&linestring).into());
-            let mut properties = JsonObject::new();
-            properties
-                .insert("name".to_owned(), JsonValue::from(index.to_string()));
+            let geometry = crate::antimeridian::line(linestring, antimeridian);
+            let properties = properties(*index, options.attributes);
             Feature {
-                bbox: None,
-                geometry: Some(geometry),
-                id: Some(geojson::feature::Id::Number(
-                    u64::from(*index).into(),
-                )),
+                bbox: options.bbox.then(|| bbox(&geometry)).flatten(),
+                geometry: Some(geojson::Geometry::new((&geometry).into())),
+                id: options.id.id(*index),
                 properties: Some(properties),
                 foreign_members: None,
             }
@@ -27,24 +77,191 @@ pub fn boundaries(indexes: &[CellIndex]) -> Vec<Feature> {
 }
 
 /// Returns `GeoJSON` features representing the indexes' centers.
-pub fn centers(indexes: &[CellIndex]) -> Vec<Feature> {
+pub fn centers(indexes: &[CellIndex], options: FeatureArgs) -> Vec<Feature> {
     indexes
         .iter()
         .copied()
         .map(|index| {
             let ll = LatLng::from(index);
-            let center = geo_types::Point(coord! {x: ll.lng(), y: ll.lat()});
-            let geometry = geojson::Geometry::new((&center).into());
-            let mut properties = JsonObject::new();
-            properties
-                .insert("name".to_owned(), JsonValue::from(index.to_string()));
+            let center =
+                Geometry::Point(coord! {x: ll.lng(), y: ll.lat()}.into());
+            let properties = properties(index, options.attributes);
             Feature {
-                bbox: None,
-                geometry: Some(geometry),
-                id: Some(geojson::feature::Id::Number(u64::from(index).into())),
+                bbox: options.bbox.then(|| bbox(&center)).flatten(),
+                geometry: Some(geojson::Geometry::new((&center).into())),
+                id: options.id.id(index),
                 properties: Some(properties),
                 foreign_members: None,
             }
         })
         .collect::<Vec<_>>()
 }
+
+/// Returns `GeoJSON` features representing the indexes' shapes, with extra
+/// properties.
+///
+/// Shapes crossing the antimeridian are split along it.
+pub fn shapes(
+    cells: impl IntoIterator<Item = (CellIndex, JsonObject)>,
+    options: FeatureArgs,
+) -> Vec<Feature> {
+    cells
+        .into_iter()
+        .map(|(index, extra)| {
+            let mut polygons = crate::antimeridian::cell(index);
+            let geometry = if polygons.0.len() == 1 {
+                Geometry::Polygon(polygons.0.remove(0))
+            } else {
+                Geometry::MultiPolygon(polygons)
+            };
+            let mut properties = properties(index, options.attributes);
+            properties.extend(extra);
+            Feature {
+                bbox: options.bbox.then(|| bbox(&geometry)).flatten(),
+                geometry: Some(geojson::Geometry::new((&geometry).into())),
+                id: options.id.id(index),
+                properties: Some(properties),
+                foreign_members: None,
+            }
+        })
+        .collect::<Vec<_>>()
+}
+
+/// Returns a `GeoJSON` feature collection.
+///
+/// If `bbox` is set, the collection has a bounding box covering the features'
+/// ones.
+pub fn collection(features: Vec<Feature>, bbox: bool) -> GeoJson {
+    let bbox = bbox
+        .then(|| {
+            extent(features.iter().filter_map(|feature| {
+                let bbox = feature.bbox.as_ref()?;
+                Some((bbox[0], bbox[1], bbox[2], bbox[3]))
+            }))
+        })
+        .flatten();
+
+    GeoJson::FeatureCollection(FeatureCollection {
+        bbox,
+        features,
+        foreign_members: None,
+    })
+}
+
+/// Returns the bounding box of a geometry, if not empty.
+///
+/// A bounding box crossing the antimeridian has its western longitude greater
+/// than its eastern one, as per RFC 7946.
+pub fn bbox(geometry: &Geometry) -> Option<Bbox> {
+    extent(
+        parts(geometry).into_iter().map(|rect| {
+            (rect.min().x, rect.min().y, rect.max().x, rect.max().y)
+        }),
+    )
+}
+
+/// Returns the bounding rectangles of the parts of a geometry.
+fn parts(geometry: &Geometry) -> Vec<Rect> {
+    match *geometry {
+        Geometry::MultiPoint(ref points) => {
+            points.iter().map(BoundingRect::bounding_rect).collect()
+        }
+        Geometry::MultiLineString(ref lines) => lines
+            .iter()
+            .filter_map(BoundingRect::bounding_rect)
+            .collect(),
+        Geometry::MultiPolygon(ref polygons) => polygons
+            .iter()
+            .filter_map(BoundingRect::bounding_rect)
+            .collect(),
+        Geometry::GeometryCollection(ref geometries) => {
+            geometries.iter().flat_map(parts).collect()
+        }
+        ref geometry => geometry.bounding_rect().into_iter().collect(),
+    }
+}
+
+/// Returns the smallest bounding box containing the given ones.
+///
+/// Boxes are given as (west, south, east, north). On the longitude axis, the
+/// boxes leave gaps around the globe: the largest one is left out of the
+/// bounding box, which may therefore cross the antimeridian.
+pub fn extent(
+    boxes: impl IntoIterator<Item = (f64, f64, f64, f64)>,
+) -> Option<Bbox> {
+    let mut ranges = Vec::new();
+    let (mut south, mut north) = (f64::INFINITY, f64::NEG_INFINITY);
+    for (west, min_lat, east, max_lat) in boxes {
+        south = south.min(min_lat);
+        north = north.max(max_lat);
+        // Normalize the range with a western bound in [-180°, 180°), splitting
+        // it if it crosses the antimeridian.
+        let east = if east < west { east + 360. } else { east };
+        if east - west >= 360. {
+            ranges.push((-180., 180.));
+            continue;
+        }
+        let offset = 360. * (west + 180.).div_euclid(360.);
+        let (west, east) = (west - offset, east - offset);
+        if east > 180. {
+            ranges.extend([(west, 180.), (-180., east - 360.)]);
+        } else {
+            ranges.push((west, east));
+        }
+    }
+    ranges.sort_by(|lhs, rhs| lhs.0.total_cmp(&rhs.0));
+    let &(first, _) = ranges.first()?;
+
+    // Find the largest gap between the ranges, starting with the one around
+    // the antimeridian: the bounding box spans everything else.
+    let mut east = f64::NEG_INFINITY;
+    let mut gaps = Vec::new();
+    for &(west, range_east) in &ranges {
+        if east.is_finite() && west > east {
+            gaps.push((west - east, west, east));
+        }
+        east = east.max(range_east);
+    }
+    let (_, west, east) = gaps.into_iter().fold(
+        (first + 360. - east, first, east),
+        |largest, gap| {
+            if gap.0 > largest.0 { gap } else { largest }
+        },
+    );
+
+    Some(vec![west, south, east, north])
+}
+
+/// Returns the properties of a cell feature.
+///
+/// The cell index is always set as `name`, while the other attributes (named
+/// as in `cellInfo`) are only set if `attributes` is set.
+fn properties(index: CellIndex, attributes: bool) -> JsonObject {
+    let mut properties = JsonObject::new();
+    properties.insert("name".to_owned(), JsonValue::from(index.to_string()));
+    if !attributes {
+        return properties;
+    }
+
+    let resolution = index.resolution();
+    let parent = resolution
+        .pred()
+        .and_then(|res| index.parent(res))
+        .map(|parent| parent.to_string());
+    let faces = index
+        .icosahedron_faces()
+        .iter()
+        .map(u8::from)
+        .collect::<Vec<_>>();
+    properties.extend([
+        ("resolution".to_owned(), u8::from(resolution).into()),
+        ("baseCell".to_owned(), u8::from(index.base_cell()).into()),
+        ("area".to_owned(), index.area_km2().into()),
+        ("isPentagon".to_owned(), index.is_pentagon().into()),
+        ("isClass3".to_owned(), resolution.is_class3().into()),
+        ("parent".to_owned(), parent.into()),
+        ("faces".to_owned(), faces.into()),
+    ]);
+
+    properties
+}
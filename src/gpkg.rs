@@ -0,0 +1,187 @@
+use crate::layer::{ColumnKind, Layer, Value};
+use anyhow::{Context, Result as AnyResult};
+use geo::BoundingRect;
+use geozero::{CoordDimensions, ToWkb};
+use rusqlite::{Connection, ToSql, params, params_from_iter, types::Null};
+use std::{fs, io, path::Path};
+
+/// WGS84 spatial reference system identifier.
+const SRS_ID: i32 = 4326;
+
+/// Write the layer into a new `GeoPackage` file.
+///
+/// The file is overwritten if it already exists.
+pub fn write(path: &Path, layer: &Layer) -> AnyResult<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => {
+            return Err(err)
+                .with_context(|| format!("remove {}", path.display()));
+        }
+        _ => {}
+    }
+    let mut conn = Connection::open(path)
+        .with_context(|| format!("create {}", path.display()))?;
+    let tx = conn.transaction().context("start transaction")?;
+
+    create_metadata(&tx).context("create GeoPackage metadata")?;
+    create_table(&tx, layer).context("create GeoPackage table")?;
+    insert_features(&tx, layer).context("insert GeoPackage features")?;
+
+    tx.commit().context("commit GeoPackage")
+}
+
+/// Create the mandatory `GeoPackage` tables.
+fn create_metadata(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "PRAGMA application_id = 1196444487;
+         PRAGMA user_version = 10400;
+         CREATE TABLE gpkg_spatial_ref_sys (
+             srs_name TEXT NOT NULL,
+             srs_id INTEGER NOT NULL PRIMARY KEY,
+             organization TEXT NOT NULL,
+             organization_coordsys_id INTEGER NOT NULL,
+             definition TEXT NOT NULL,
+             description TEXT
+         );
+         INSERT INTO gpkg_spatial_ref_sys VALUES
+             ('Undefined cartesian SRS', -1, 'NONE', -1, 'undefined', NULL),
+             ('Undefined geographic SRS', 0, 'NONE', 0, 'undefined', NULL),
+             ('WGS 84 geodetic', 4326, 'EPSG', 4326,
+              'GEOGCS[\"WGS 84\",DATUM[\"WGS_1984\",SPHEROID[\"WGS 84\",6378137,298.257223563,AUTHORITY[\"EPSG\",\"7030\"]],AUTHORITY[\"EPSG\",\"6326\"]],PRIMEM[\"Greenwich\",0,AUTHORITY[\"EPSG\",\"8901\"]],UNIT[\"degree\",0.0174532925199433,AUTHORITY[\"EPSG\",\"9122\"]],AUTHORITY[\"EPSG\",\"4326\"]]',
+              'longitude/latitude coordinates in decimal degrees on the WGS 84 spheroid');
+         CREATE TABLE gpkg_contents (
+             table_name TEXT NOT NULL PRIMARY KEY,
+             data_type TEXT NOT NULL,
+             identifier TEXT UNIQUE,
+             description TEXT DEFAULT '',
+             last_change DATETIME NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ','now')),
+             min_x DOUBLE,
+             min_y DOUBLE,
+             max_x DOUBLE,
+             max_y DOUBLE,
+             srs_id INTEGER REFERENCES gpkg_spatial_ref_sys(srs_id)
+         );
+         CREATE TABLE gpkg_geometry_columns (
+             table_name TEXT NOT NULL REFERENCES gpkg_contents(table_name),
+             column_name TEXT NOT NULL,
+             geometry_type_name TEXT NOT NULL,
+             srs_id INTEGER NOT NULL REFERENCES gpkg_spatial_ref_sys(srs_id),
+             z TINYINT NOT NULL,
+             m TINYINT NOT NULL,
+             PRIMARY KEY (table_name, column_name)
+         );
+         CREATE TABLE gpkg_extensions (
+             table_name TEXT,
+             column_name TEXT,
+             extension_name TEXT NOT NULL,
+             definition TEXT NOT NULL,
+             scope TEXT NOT NULL,
+             UNIQUE (table_name, column_name, extension_name)
+         );",
+    )
+}
+
+/// Create and register the feature table, along with its spatial index.
+fn create_table(conn: &Connection, layer: &Layer) -> rusqlite::Result<()> {
+    let columns = ["fid INTEGER PRIMARY KEY AUTOINCREMENT", "geom GEOMETRY"]
+        .into_iter()
+        .map(ToOwned::to_owned)
+        .chain(layer.columns.iter().map(|column| {
+            let kind = match column.kind {
+                ColumnKind::Integer => "INTEGER",
+                ColumnKind::Text => "TEXT",
+            };
+            format!("{} {kind}", quote(&column.name))
+        }))
+        .collect::<Vec<_>>();
+    conn.execute_batch(&format!(
+        "CREATE TABLE {} ({});
+         CREATE VIRTUAL TABLE {} USING rtree(id, minx, maxx, miny, maxy);",
+        quote(&layer.name),
+        columns.join(", "),
+        quote(&format!("rtree_{}_geom", layer.name)),
+    ))?;
+
+    let extent = layer.extent();
+    conn.execute(
+        "INSERT INTO gpkg_contents
+             (table_name, data_type, identifier, min_x, min_y, max_x, max_y, srs_id)
+         VALUES (?1, 'features', ?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            layer.name,
+            extent.map(|rect| rect.min().x),
+            extent.map(|rect| rect.min().y),
+            extent.map(|rect| rect.max().x),
+            extent.map(|rect| rect.max().y),
+            SRS_ID,
+        ],
+    )?;
+    conn.execute(
+        "INSERT INTO gpkg_geometry_columns VALUES (?1, 'geom', 'GEOMETRY', ?2, 0, 0)",
+        params![layer.name, SRS_ID],
+    )?;
+    // Triggers are omitted: the file is written once and not meant to be
+    // edited through this tool.
+    conn.execute(
+        "INSERT INTO gpkg_extensions VALUES
+             (?1, 'geom', 'gpkg_rtree_index', 'http://www.geopackage.org/spec/#extension_rtree', 'write-only')",
+        params![layer.name],
+    )?;
+
+    Ok(())
+}
+
+/// Insert the features, and their bounding boxes in the spatial index.
+fn insert_features(conn: &Connection, layer: &Layer) -> AnyResult<()> {
+    let names = std::iter::once("geom".to_owned())
+        .chain(layer.columns.iter().map(|column| quote(&column.name)))
+        .collect::<Vec<_>>();
+    let placeholders = (1..=names.len())
+        .map(|i| format!("?{i}"))
+        .collect::<Vec<_>>();
+    let mut insert = conn.prepare(&format!(
+        "INSERT INTO {} ({}) VALUES ({})",
+        quote(&layer.name),
+        names.join(", "),
+        placeholders.join(", "),
+    ))?;
+    let mut index = conn.prepare(&format!(
+        "INSERT INTO {} VALUES (?1, ?2, ?3, ?4, ?5)",
+        quote(&format!("rtree_{}_geom", layer.name)),
+    ))?;
+
+    for feature in &layer.features {
+        let rect = feature.geometry.bounding_rect();
+        let envelope = rect.map_or_else(Vec::new, |rect| {
+            vec![rect.min().x, rect.max().x, rect.min().y, rect.max().y]
+        });
+        let blob = feature
+            .geometry
+            .to_gpkg_wkb(CoordDimensions::xy(), Some(SRS_ID), envelope)
+            .context("encode geometry")?;
+        let values = feature.values.iter().map(|value| match *value {
+            Value::Integer(ref value) => value as &dyn ToSql,
+            Value::Text(ref value) => value,
+            Value::Null => &Null,
+        });
+        insert.execute(params_from_iter(
+            std::iter::once(&blob as &dyn ToSql).chain(values),
+        ))?;
+        if let Some(rect) = rect {
+            index.execute(params![
+                conn.last_insert_rowid(),
+                rect.min().x,
+                rect.max().x,
+                rect.min().y,
+                rect.max().y,
+            ])?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Quote an SQL identifier.
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
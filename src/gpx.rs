@@ -0,0 +1,116 @@
+use anyhow::{Context, Result as AnyResult};
+use geojson::{JsonObject, JsonValue};
+use gpx::{Gpx, Waypoint};
+use h3o::{CellIndex, LatLng, Resolution, geom::PlotterBuilder};
+use std::io::Read;
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+
+/// A cell crossed by a GPX track.
+pub struct TracePoint {
+    /// Crossed cell.
+    pub cell: CellIndex,
+    /// Index of the track in the GPX document.
+    pub track: usize,
+    /// Index of the segment in the track.
+    pub segment: usize,
+    /// RFC 3339 timestamp of the track point, if any.
+    pub time: Option<String>,
+}
+
+/// Parse a GPX document.
+pub fn read(input: impl Read) -> AnyResult<Gpx> {
+    gpx::read(input).context("parse GPX")
+}
+
+/// Extract the waypoints, tracks and routes of a GPX document as geometries.
+///
+/// Waypoints are returned as points, routes as linestrings and tracks as
+/// multilinestrings (one linestring per segment).
+pub fn to_features(gpx: Gpx) -> Vec<(geo_types::Geometry<f64>, JsonObject)> {
+    let waypoints = gpx.waypoints.into_iter().map(|waypoint| {
+        let mut properties = properties(waypoint.name.clone());
+        if let Some(time) = waypoint.time.and_then(format_time) {
+            properties.insert("time".to_owned(), JsonValue::from(time));
+        }
+        (waypoint.into(), properties)
+    });
+    let tracks = gpx.tracks.into_iter().map(|track| {
+        let properties = properties(track.name.clone());
+        (track.into(), properties)
+    });
+    let routes = gpx.routes.into_iter().map(|route| {
+        let properties = properties(route.name.clone());
+        (route.into(), properties)
+    });
+
+    waypoints.chain(tracks).chain(routes).collect()
+}
+
+/// Return the cells crossed by each track segment, in order.
+///
+/// Each cell is tagged with the timestamp of the track point from which the
+/// path entered it.
+pub fn trace(gpx: &Gpx, resolution: Resolution) -> AnyResult<Vec<TracePoint>> {
+    let mut trace = Vec::new();
+
+    for (track, segment, points) in
+        gpx.tracks.iter().enumerate().flat_map(|(i, track)| {
+            track
+                .segments
+                .iter()
+                .enumerate()
+                .map(move |(j, segment)| (i, j, &segment.points))
+        })
+    {
+        let mut previous: Option<&Waypoint> = None;
+        for point in points {
+            let ll = LatLng::try_from(point.point().0)
+                .with_context(|| format!("invalid point in track #{track}"))?;
+            let cell = ll.to_cell(resolution);
+            let cells = match previous {
+                // Skip the first cell: it has been emitted with the previous
+                // point.
+                Some(from) => {
+                    let mut plotter = PlotterBuilder::new(resolution).build();
+                    plotter
+                        .add(geo_types::Line::new(from.point(), point.point()))
+                        .context("invalid track segment")?;
+                    plotter
+                        .plot()
+                        .skip(1)
+                        .collect::<Result<Vec<_>, _>>()
+                        .context("track plot failed")?
+                }
+                None => vec![cell],
+            };
+            let time = previous.unwrap_or(point).time;
+            for path_cell in cells {
+                // The point's own cell is tagged with the point's timestamp.
+                let time = if path_cell == cell { point.time } else { time };
+                trace.push(TracePoint {
+                    cell: path_cell,
+                    track,
+                    segment,
+                    time: time.and_then(format_time),
+                });
+            }
+            previous = Some(point);
+        }
+    }
+
+    Ok(trace)
+}
+
+/// Build the properties of a GPX element.
+fn properties(name: Option<String>) -> JsonObject {
+    let mut properties = JsonObject::new();
+    if let Some(name) = name {
+        properties.insert("name".to_owned(), JsonValue::from(name));
+    }
+    properties
+}
+
+/// Format a GPX timestamp as RFC 3339.
+fn format_time(time: gpx::Time) -> Option<String> {
+    OffsetDateTime::from(time).format(&Rfc3339).ok()
+}
@@ -1,7 +1,29 @@
-use crate::index::Index;
+use crate::{geohash::Geohash, index::Index, tile::Tile};
 use anyhow::{Context, Result as AnyResult};
 use h3o::{CellIndex, LatLng};
-use std::io;
+use std::{
+    fs::File,
+    io::{self, BufRead},
+    path::Path,
+};
+
+/// Open the given file for reading, or `stdin` if no path is given.
+pub fn open(path: Option<&Path>) -> AnyResult<Box<dyn io::Read>> {
+    Ok(match path {
+        Some(path) => Box::new(
+            File::open(path)
+                .with_context(|| format!("open {}", path.display()))?,
+        ),
+        None => Box::new(io::stdin()),
+    })
+}
+
+/// Create the given file for writing (buffered).
+pub fn create(path: &Path) -> AnyResult<io::BufWriter<File>> {
+    File::create(path)
+        .map(io::BufWriter::new)
+        .with_context(|| format!("create {}", path.display()))
+}
 
 /// Read cell indexes from stdin.
 pub fn read_cell_indexes() -> impl Iterator<Item = AnyResult<CellIndex>> {
@@ -14,6 +36,82 @@ pub fn read_cell_indexes() -> impl Iterator<Item = AnyResult<CellIndex>> {
     })
 }
 
+/// Read cell indexes from a file, one per line.
+pub fn read_cell_file(path: &Path) -> AnyResult<Vec<CellIndex>> {
+    io::BufReader::new(open(Some(path))?)
+        .lines()
+        .map(|input| {
+            input
+                .with_context(|| format!("read line from {}", path.display()))
+                .and_then(|line| {
+                    line.trim_end().parse().with_context(|| {
+                        format!(
+                            "cannot parse {line} as CellIndex in {}",
+                            path.display()
+                        )
+                    })
+                })
+        })
+        .collect()
+}
+
+/// Read cell indexes from stdin, along with the whitespace-separated fields
+/// that follow them on each line.
+pub fn read_cell_records()
+-> impl Iterator<Item = AnyResult<(CellIndex, Vec<String>)>> {
+    io::stdin().lines().map(|input| {
+        input.context("read line from stdin").and_then(|line| {
+            let mut fields = line.split_whitespace();
+            let index = fields.next().unwrap_or_default();
+            let index = index.parse().with_context(|| {
+                format!("cannot parse {index} as CellIndex")
+            })?;
+
+            Ok((index, fields.map(ToOwned::to_owned).collect()))
+        })
+    })
+}
+
+/// Read coordinates from stdin, along with the whitespace-separated fields
+/// that follow them on each line.
+pub fn read_coord_records()
+-> impl Iterator<Item = AnyResult<(LatLng, Vec<String>)>> {
+    io::stdin().lines().map(|input| {
+        input.context("read line from stdin").and_then(|line| {
+            let mut fields = line.split_whitespace();
+            let lat = fields.next().unwrap_or_default();
+            let lat = lat.parse::<f64>().context("latitude")?;
+            let lng = fields.next().unwrap_or_default();
+            let lng = lng.parse::<f64>().context("longitude")?;
+            let coord = LatLng::new(lat, lng).context("lat/lng")?;
+
+            Ok((coord, fields.map(ToOwned::to_owned).collect()))
+        })
+    })
+}
+
+/// Read geohashes from stdin.
+pub fn read_geohashes() -> impl Iterator<Item = AnyResult<Geohash>> {
+    io::stdin().lines().map(|input| {
+        input.context("read line from stdin").and_then(|line| {
+            line.trim_end()
+                .parse()
+                .with_context(|| format!("cannot parse {line} as geohash"))
+        })
+    })
+}
+
+/// Read Web Mercator tiles (`z/x/y` or quadkey) from stdin.
+pub fn read_tiles() -> impl Iterator<Item = AnyResult<Tile>> {
+    io::stdin().lines().map(|input| {
+        input.context("read line from stdin").and_then(|line| {
+            line.trim_end()
+                .parse()
+                .with_context(|| format!("cannot parse {line} as tile"))
+        })
+    })
+}
+
 pub fn read_indexes() -> impl Iterator<Item = AnyResult<Index>> {
     io::stdin().lines().map(|input| {
         input.context("read line from stdin").and_then(|line| {
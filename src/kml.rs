@@ -1,28 +1,291 @@
-use anyhow::{Context, Result as AnyResult};
+use anyhow::{Context, Error as AnyError, Result as AnyResult, ensure};
 use geo_types::{LineString, coord};
 use h3o::{CellIndex, LatLng};
 use kml::{Kml, KmlDocument, KmlWriter, types::Folder};
 use maplit::hashmap;
-use std::io;
+use std::{
+    collections::BTreeMap,
+    fmt,
+    io::{self, Write as _},
+    num::NonZeroUsize,
+    str::FromStr,
+};
+use zip::{CompressionMethod, ZipWriter, write::SimpleFileOptions};
+
+/// KML styling options.
+#[derive(clap::Args, Debug)]
+#[command(next_help_heading = "KML options")]
+pub struct StyleArgs {
+    /// Name of the KML document.
+    #[arg(long, default_value = "H3 Geometry")]
+    pub name: String,
+
+    /// Description of the KML document.
+    #[arg(long)]
+    pub description: Option<String>,
+
+    /// Line color, as `RRGGBB` or `RRGGBBAA` hex (also used for labels).
+    #[arg(long, default_value = "ff0000")]
+    pub line_color: Color,
+
+    /// Line width, in pixels.
+    #[arg(long, default_value_t = 2.)]
+    pub line_width: f64,
+
+    /// Fill color, as `RRGGBB` or `RRGGBBAA` hex (polygons only).
+    #[arg(long)]
+    pub fill_color: Option<Color>,
+
+    /// Fill opacity, from 0 to 1 (polygons only).
+    ///
+    /// Fill with the line color if no fill color is given.
+    #[arg(long, value_parser = parse_opacity)]
+    pub fill_opacity: Option<f64>,
+
+    /// URL of the icon (points only).
+    #[arg(
+        long,
+        default_value = "http://maps.google.com/mapfiles/kml/shapes/placemark_circle.png"
+    )]
+    pub icon: String,
+}
+
+impl StyleArgs {
+    /// Return the document description, defaulting to the generating command.
+    pub fn description(&self, command: &str) -> String {
+        self.description
+            .clone()
+            .unwrap_or_else(|| format!("Generated by {command}"))
+    }
+
+    /// Return the style of lines and polygons.
+    ///
+    /// `color`, if any, replaces the configured line and fill colors.
+    pub fn shape_style(&self, id: String, color: Option<Color>) -> Kml {
+        let fill = self
+            .fill_color
+            .or_else(|| self.fill_opacity.map(|_| self.line_color))
+            .map(|fill| color.unwrap_or(fill));
+
+        Kml::Style(kml::types::Style {
+            id: Some(id),
+            line: Some(kml::types::LineStyle {
+                color: color.unwrap_or(self.line_color).to_string(),
+                width: self.line_width,
+                ..kml::types::LineStyle::default()
+            }),
+            poly: fill.map(|fill| kml::types::PolyStyle {
+                color: self
+                    .fill_opacity
+                    .map_or(fill, |opacity| fill.with_opacity(opacity))
+                    .to_string(),
+                fill: true,
+                outline: true,
+                ..kml::types::PolyStyle::default()
+            }),
+            ..kml::types::Style::default()
+        })
+    }
+
+    /// Return the style of points, along with its highlighted variant.
+    ///
+    /// `color`, if any, is used to tint the icon.
+    pub fn icon_style(&self, id: String, color: Option<Color>) -> Vec<Kml> {
+        let style = |id: String, scale| {
+            Kml::Style(kml::types::Style {
+                id: Some(id),
+                icon: Some(kml::types::IconStyle {
+                    scale,
+                    icon: kml::types::Icon {
+                        href: self.icon.clone(),
+                        ..kml::types::Icon::default()
+                    },
+                    hot_spot: Some(kml::types::Vec2 {
+                        x: 20.,
+                        y: 2.,
+                        xunits: kml::types::Units::Pixels,
+                        yunits: kml::types::Units::Pixels,
+                    }),
+                    color: color.map_or_else(
+                        || "ffffffff".to_owned(),
+                        |color| color.to_string(),
+                    ),
+                    ..kml::types::IconStyle::default()
+                }),
+                label: Some(kml::types::LabelStyle {
+                    color: self.line_color.to_string(),
+                    scale: 2.,
+                    ..kml::types::LabelStyle::default()
+                }),
+                ..kml::types::Style::default()
+            })
+        };
+        let normal = format!("{id}_normal");
+        let highlight = format!("{id}_highlight");
+        let style_map = kml::types::StyleMap {
+            id: Some(id),
+            pairs: vec![
+                kml::types::Pair {
+                    key: "normal".to_owned(),
+                    style_url: format!("#{normal}"),
+                    ..kml::types::Pair::default()
+                },
+                kml::types::Pair {
+                    key: "highlight".to_owned(),
+                    style_url: format!("#{highlight}"),
+                    ..kml::types::Pair::default()
+                },
+            ],
+            ..kml::types::StyleMap::default()
+        };
+
+        vec![
+            style(normal, 1.1),
+            style(highlight, 1.3),
+            Kml::StyleMap(style_map),
+        ]
+    }
+}
+
+/// An RGBA color.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Color {
+    red: u8,
+    green: u8,
+    blue: u8,
+    alpha: u8,
+}
+
+impl Color {
+    /// Return the same color with the given opacity, from 0 to 1.
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "opacity is in [0, 1]"
+    )]
+    fn with_opacity(self, opacity: f64) -> Self {
+        Self {
+            alpha: (opacity.clamp(0., 1.) * 255.).round() as u8,
+            ..self
+        }
+    }
+}
+
+impl FromStr for Color {
+    type Err = AnyError;
+
+    /// Parse a `RRGGBB` or `RRGGBBAA` hex color, optionally prefixed by `#`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex = s.strip_prefix('#').unwrap_or(s);
+        ensure!(
+            matches!(hex.len(), 6 | 8) && hex.is_ascii(),
+            "invalid color {s} (expected RRGGBB or RRGGBBAA)"
+        );
+        let channel = |i: usize| {
+            hex.get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .with_context(|| format!("invalid hex color {s}"))
+        };
+
+        Ok(Self {
+            red: channel(0)?,
+            green: channel(2)?,
+            blue: channel(4)?,
+            alpha: if hex.len() == 8 { channel(6)? } else { 0xff },
+        })
+    }
+}
+
+impl fmt::Display for Color {
+    /// Format the color as KML does (`aabbggrr`).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:02x}{:02x}{:02x}{:02x}",
+            self.alpha, self.blue, self.green, self.red
+        )
+    }
+}
+
+/// Parse an opacity, from 0 to 1.
+fn parse_opacity(s: &str) -> AnyResult<f64> {
+    let opacity = s.parse::<f64>().context("invalid opacity")?;
+    ensure!(
+        (0. ..=1.).contains(&opacity),
+        "opacity must be between 0 and 1"
+    );
+    Ok(opacity)
+}
+
+/// Return the styles of a set of cells, along with the style URL of each cell.
+///
+/// Cells without a color of their own share the `id` style, while cells of the
+/// same color share a `{id}_{color}` style built from `style`.
+pub fn cell_styles(
+    id: &str,
+    colors: impl IntoIterator<Item = Option<Color>>,
+    style: impl Fn(String, Option<Color>) -> Vec<Kml>,
+) -> (Vec<Kml>, Vec<String>) {
+    let mut styles = BTreeMap::new();
+    let mut urls = Vec::new();
+    for color in colors {
+        let style_id = color
+            .map_or_else(|| id.to_owned(), |color| format!("{id}_{color}"));
+        urls.push(format!("#{style_id}"));
+        styles
+            .entry(style_id.clone())
+            .or_insert_with(|| style(style_id, color));
+    }
+
+    (styles.into_values().flatten().collect(), urls)
+}
+
+/// Read the color of a cell from its input fields.
+///
+/// `field` is the 1-based index of the field holding the color, if any.
+pub fn cell_color(
+    index: CellIndex,
+    fields: &[String],
+    field: Option<NonZeroUsize>,
+) -> AnyResult<Option<Color>> {
+    field
+        .map(|field| {
+            fields
+                .get(field.get() - 1)
+                .with_context(|| format!("missing color field for {index}"))?
+                .parse()
+                .with_context(|| format!("invalid color for {index}"))
+        })
+        .transpose()
+}
 
 /// Return KML Placemarks representing the indexes' boundaries.
-pub fn boundaries(indexes: &[CellIndex], style: &str) -> Vec<Kml> {
-    indexes
+///
+/// Each index comes with the URL of its style. Boundaries are either outlines
+/// or, if `polygon` is set, closed polygons that can be filled. Boundaries
+/// crossing the antimeridian are fixed according to `antimeridian`.
+pub fn boundaries(
+    cells: &[(CellIndex, String)],
+    polygon: bool,
+    antimeridian: crate::antimeridian::Mode,
+) -> Vec<Kml> {
+    cells
         .iter()
-        .map(|index| {
+        .map(|&(index, ref style_url)| {
             let mut linestring: LineString = index.boundary().into();
             linestring.close();
-            let geometry = kml::types::LineString {
-                coords: linestring.0.into_iter().map(Into::into).collect(),
-                tessellate: true,
-                ..kml::types::LineString::default()
+            let geometry = if polygon {
+                crate::antimeridian::polygon(
+                    geo_types::Polygon::new(linestring, Vec::new()),
+                    antimeridian,
+                )
+            } else {
+                crate::antimeridian::line(linestring, antimeridian)
             };
             let placemark = kml::types::Placemark {
                 name: Some(index.to_string()),
-                attrs: hashmap! {
-                    "styleUrl".to_owned() => format!("#{style}"),
-                },
-                geometry: Some(kml::types::Geometry::LineString(geometry)),
+                style_url: Some(style_url.clone()),
+                geometry: Some(tessellated(geometry)),
                 ..kml::types::Placemark::default()
             };
             Kml::Placemark(placemark)
@@ -31,11 +294,12 @@ pub fn boundaries(indexes: &[CellIndex], style: &str) -> Vec<Kml> {
 }
 
 /// Return KML Placemarks representing the indexes centers.
-pub fn centers(indexes: &[CellIndex], style: &str) -> Vec<Kml> {
-    indexes
+///
+/// Each index comes with the URL of its style.
+pub fn centers(cells: &[(CellIndex, String)]) -> Vec<Kml> {
+    cells
         .iter()
-        .copied()
-        .map(|index| {
+        .map(|&(index, ref style_url)| {
             let ll = LatLng::from(index);
             let geometry = kml::types::Point {
                 coord: coord! {x: ll.lng(), y: ll.lat()}.into(),
@@ -44,9 +308,7 @@ pub fn centers(indexes: &[CellIndex], style: &str) -> Vec<Kml> {
             };
             let placemark = kml::types::Placemark {
                 name: Some(index.to_string()),
-                attrs: hashmap! {
-                    "styleUrl".to_owned() => format!("#{style}"),
-                },
+                style_url: Some(style_url.clone()),
                 geometry: Some(kml::types::Geometry::Point(geometry)),
                 ..kml::types::Placemark::default()
             };
@@ -64,6 +326,44 @@ pub fn print_document(
     name: String,
     description: String,
     elements: Vec<Kml>,
+) -> AnyResult<()> {
+    write_document(io::stdout().lock(), name, description, elements)
+        .context("write KML to stdout")
+}
+
+/// Write the given KML elements as a KMZ archive.
+///
+/// The archive contains a single `doc.kml` entry, as expected by viewers.
+pub fn write_kmz(
+    out: impl io::Write,
+    name: String,
+    description: String,
+    elements: Vec<Kml>,
+) -> AnyResult<()> {
+    let mut document = Vec::new();
+    write_document(&mut document, name, description, elements)?;
+
+    // Built in memory since ZIP archives require a seekable output.
+    let mut archive = ZipWriter::new(io::Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated);
+    archive
+        .start_file("doc.kml", options)
+        .context("create KMZ entry")?;
+    archive.write_all(&document).context("write KMZ entry")?;
+    let archive = archive.finish().context("finalize KMZ")?;
+
+    let mut out = out;
+    out.write_all(archive.get_ref()).context("write KMZ")?;
+    out.flush().context("flush KMZ")
+}
+
+/// Write the given KML elements as a KML document.
+fn write_document(
+    mut out: impl io::Write,
+    name: String,
+    description: String,
+    elements: Vec<Kml>,
 ) -> AnyResult<()> {
     let document = KmlDocument::<f64> {
         version: kml::KmlVersion::V22,
@@ -82,39 +382,72 @@ pub fn print_document(
         })],
     };
 
-    let mut stdout = io::stdout().lock();
-    let mut writer = KmlWriter::from_writer(&mut stdout);
     let kml = Kml::KmlDocument(document);
 
-    println!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
-    writer.write(&kml).context("write KML to stdout")?;
+    writeln!(out, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")
+        .context("write XML declaration")?;
+    KmlWriter::from_writer(&mut out)
+        .write(&kml)
+        .context("write KML")?;
 
     Ok(())
 }
 
 /// Return a KML Placemark representing the indexes' polygons.
-pub fn polygons(polygons: geo_types::MultiPolygon, style: &str) -> Kml {
+pub fn polygons(
+    polygons: geo_types::MultiPolygon,
+    name: Option<String>,
+    style: &str,
+) -> Kml {
     let geometries = kml::types::MultiGeometry::new(
         polygons
             .into_iter()
-            .map(|polygon| {
-                let mut polygon = kml::types::Polygon::from(polygon);
-                polygon.tessellate = true;
-                kml::types::Geometry::Polygon(polygon)
-            })
+            .map(|polygon| tessellated(polygon.into()))
             .collect(),
     );
 
     let placemark = kml::types::Placemark {
-        attrs: hashmap! {
-            "styleUrl".to_owned() => format!("#{style}"),
-        },
+        name,
+        style_url: Some(format!("#{style}")),
         geometry: Some(kml::types::Geometry::MultiGeometry(geometries)),
         ..kml::types::Placemark::default()
     };
     Kml::Placemark(placemark)
 }
 
+/// Convert a geometry to KML, with lines and polygons following the terrain.
+fn tessellated(geometry: geo_types::Geometry) -> kml::types::Geometry {
+    match geometry {
+        geo_types::Geometry::LineString(line) => {
+            let mut line = kml::types::LineString::from(line);
+            line.tessellate = true;
+            kml::types::Geometry::LineString(line)
+        }
+        geo_types::Geometry::Polygon(polygon) => {
+            let mut polygon = kml::types::Polygon::from(polygon);
+            polygon.tessellate = true;
+            kml::types::Geometry::Polygon(polygon)
+        }
+        geo_types::Geometry::MultiLineString(lines) => {
+            kml::types::Geometry::MultiGeometry(kml::types::MultiGeometry::new(
+                lines
+                    .into_iter()
+                    .map(|line| tessellated(line.into()))
+                    .collect(),
+            ))
+        }
+        geo_types::Geometry::MultiPolygon(polygons) => {
+            kml::types::Geometry::MultiGeometry(kml::types::MultiGeometry::new(
+                polygons
+                    .into_iter()
+                    .map(|polygon| tessellated(polygon.into()))
+                    .collect(),
+            ))
+        }
+        geometry => geometry.into(),
+    }
+}
+
 /// Extract geometries from a KML element.
 pub fn to_geometry(kml: Kml) -> AnyResult<Option<geo_types::Geometry>> {
     Ok(match kml {
@@ -0,0 +1,161 @@
+use anyhow::{Context, Result as AnyResult};
+use geo::BoundingRect;
+use geo_types::{Geometry, LineString, Polygon, Rect};
+use h3o::CellIndex;
+use std::{io, path::Path};
+
+/// An indexed layer format.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    Flatgeobuf,
+    Gpkg,
+}
+
+/// Write the layer in the given format.
+///
+/// `FlatGeobuf` is written on `stdout` if no output file is given, while
+/// `GeoPackage` requires an output file.
+pub fn write(
+    layer: Layer,
+    format: Format,
+    output: Option<&Path>,
+) -> AnyResult<()> {
+    match (format, output) {
+        (Format::Flatgeobuf, Some(path)) => {
+            crate::fgb::write(crate::io::create(path)?, layer)
+        }
+        (Format::Flatgeobuf, None) => {
+            crate::fgb::write(io::stdout().lock(), layer)
+        }
+        (Format::Gpkg, path) => {
+            let path =
+                path.context("GeoPackage output requires an output file")?;
+            crate::gpkg::write(path, &layer)
+        }
+    }
+}
+
+/// A layer of features sharing the same attribute columns.
+pub struct Layer {
+    /// Layer name.
+    pub name: String,
+    /// Attribute columns.
+    pub columns: Vec<Column>,
+    /// Features of the layer.
+    pub features: Vec<Feature>,
+}
+
+impl Layer {
+    /// Returns the extent of the layer, if not empty.
+    pub fn extent(&self) -> Option<Rect> {
+        self.features
+            .iter()
+            .filter_map(|feature| feature.geometry.bounding_rect())
+            .reduce(|acc, rect| {
+                Rect::new(
+                    (
+                        acc.min().x.min(rect.min().x),
+                        acc.min().y.min(rect.min().y),
+                    ),
+                    (
+                        acc.max().x.max(rect.max().x),
+                        acc.max().y.max(rect.max().y),
+                    ),
+                )
+            })
+    }
+}
+
+/// Returns a layer of cell boundaries (as polygons).
+///
+/// Each feature has the cell index and resolution as attributes, followed by
+/// the cell extra fields. Extra columns are named from `names`, or `fieldN`
+/// when there is no name for the Nth field.
+pub fn boundaries(
+    name: String,
+    cells: Vec<(CellIndex, Vec<String>)>,
+    names: &[String],
+) -> Layer {
+    let field_count = cells
+        .iter()
+        .map(|cell| cell.1.len())
+        .max()
+        .unwrap_or_default();
+    let mut columns = vec![Column::text("cell"), Column::integer("resolution")];
+    columns.extend((0..field_count).map(|i| {
+        names.get(i).map_or_else(
+            || Column::text(format!("field{}", i + 1)),
+            Column::text,
+        )
+    }));
+
+    let features = cells
+        .into_iter()
+        .map(|(index, fields)| {
+            let mut ring: LineString = index.boundary().into();
+            ring.close();
+            let mut values = vec![
+                Value::Text(index.to_string()),
+                Value::Integer(u8::from(index.resolution()).into()),
+            ];
+            values.extend(fields.into_iter().map(Value::Text));
+            values.resize_with(columns.len(), || Value::Null);
+            Feature {
+                geometry: Geometry::Polygon(Polygon::new(ring, Vec::new())),
+                values,
+            }
+        })
+        .collect();
+
+    Layer {
+        name,
+        columns,
+        features,
+    }
+}
+
+/// An attribute column.
+pub struct Column {
+    /// Column name.
+    pub name: String,
+    /// Type of the column values.
+    pub kind: ColumnKind,
+}
+
+impl Column {
+    /// Returns a new integer column.
+    pub fn integer(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            kind: ColumnKind::Integer,
+        }
+    }
+
+    /// Returns a new text column.
+    pub fn text(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            kind: ColumnKind::Text,
+        }
+    }
+}
+
+/// Type of column values.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ColumnKind {
+    Integer,
+    Text,
+}
+
+/// A geometry and its attributes, in column order.
+pub struct Feature {
+    pub geometry: Geometry,
+    pub values: Vec<Value>,
+}
+
+/// An attribute value.
+pub enum Value {
+    Integer(i64),
+    Text(String),
+    Null,
+}
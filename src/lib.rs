@@ -76,7 +76,6 @@
     clippy::string_add,
     clippy::string_lit_as_bytes,
     clippy::string_slice,
-    clippy::string_to_string,
     clippy::suboptimal_flops,
     clippy::suspicious_operation_groupings,
     clippy::todo,
@@ -105,15 +104,37 @@
     clippy::missing_errors_doc,
     // Used by some subcommands.
     clippy::use_debug,
+    // Format crates don't always agree on their dependencies' versions.
+    clippy::multiple_crate_versions,
     reason = "allow some exceptions"
 )]
 
 // }}}
 
+mod antimeridian;
+mod cellset;
 pub mod commands;
+mod fgb;
+mod geohash;
 mod geojson;
+mod gpkg;
+mod gpx;
 mod index;
 mod io;
 mod json;
 mod kml;
+mod layer;
+mod mbtiles;
+mod mvt;
+mod pmtiles;
+mod png;
+mod polyfill;
+mod raster;
+mod render;
+mod shapefile;
+mod simplify;
+mod svg;
+mod table;
+mod tile;
+mod tileset;
 mod utils;
@@ -12,26 +12,44 @@ pub struct Args {
 #[derive(Parser, Debug)]
 #[command(rename_all = "camelCase")]
 enum Command {
+    Aggregate(commands::aggregate::Args),
+    BinPoints(commands::bin_points::Args),
     CellInfo(commands::cell_info::Args),
     CellToBoundary(commands::cell_to_boundary::Args),
     CellToChildren(commands::cell_to_children::Args),
+    CellToGeohashes(commands::cell_to_geohashes::Args),
     CellToLatLng(commands::cell_to_latlng::Args),
     CellToLocalIj(commands::cell_to_local_ij::Args),
     CellToParent(commands::cell_to_parent::Args),
     CellToPolygon(commands::cell_to_polygon::Args),
+    CellToTiles(commands::cell_to_tiles::Args),
     Compact(commands::compact::Args),
     Compress(commands::compress::Args),
     Decompress(commands::decompress::Args),
+    GeohashToCells(commands::geohash_to_cells::Args),
     GeomToCells(commands::geom_to_cells::Args),
     GridDisk(commands::grid_disk::Args),
     GridPath(commands::grid_path::Args),
     IndexDecode(commands::index_decode::Args),
     LatLngToCell(commands::latlng_to_cell::Args),
+    Normalize(commands::normalize::Args),
+    Preview(commands::preview::Args),
+    Render(commands::render::Args),
     ResolutionInfo(commands::resolution_info::Args),
+    SetOp(commands::set_op::Args),
+    Stats(commands::stats::Args),
+    TileToCells(commands::tile_to_cells::Args),
+    Tiles(commands::tiles::Args),
 }
 
 fn main() -> AnyResult<()> {
     match Args::parse().command {
+        Command::Aggregate(args) => {
+            commands::aggregate::run(&args)?;
+        }
+        Command::BinPoints(args) => {
+            commands::bin_points::run(&args)?;
+        }
         Command::CellInfo(args) => {
             commands::cell_info::run(&args)?;
         }
@@ -41,6 +59,9 @@ fn main() -> AnyResult<()> {
         Command::CellToChildren(args) => {
             commands::cell_to_children::run(&args)?;
         }
+        Command::CellToGeohashes(args) => {
+            commands::cell_to_geohashes::run(&args)?;
+        }
         Command::CellToLatLng(args) => {
             commands::cell_to_latlng::run(&args)?;
         }
@@ -53,6 +74,9 @@ fn main() -> AnyResult<()> {
         Command::CellToPolygon(args) => {
             commands::cell_to_polygon::run(&args)?;
         }
+        Command::CellToTiles(args) => {
+            commands::cell_to_tiles::run(&args)?;
+        }
         Command::Compact(args) => {
             commands::compact::run(&args)?;
         }
@@ -65,6 +89,9 @@ fn main() -> AnyResult<()> {
         Command::IndexDecode(args) => {
             commands::index_decode::run(&args)?;
         }
+        Command::GeohashToCells(args) => {
+            commands::geohash_to_cells::run(&args)?;
+        }
         Command::GeomToCells(args) => {
             commands::geom_to_cells::run(&args)?;
         }
@@ -77,9 +104,30 @@ fn main() -> AnyResult<()> {
         Command::LatLngToCell(args) => {
             commands::latlng_to_cell::run(&args)?;
         }
+        Command::Normalize(args) => {
+            commands::normalize::run(&args)?;
+        }
+        Command::Preview(args) => {
+            commands::preview::run(&args)?;
+        }
+        Command::Render(args) => {
+            commands::render::run(&args)?;
+        }
         Command::ResolutionInfo(args) => {
             commands::resolution_info::run(&args)?;
         }
+        Command::SetOp(args) => {
+            commands::set_op::run(&args)?;
+        }
+        Command::Stats(args) => {
+            commands::stats::run(&args)?;
+        }
+        Command::TileToCells(args) => {
+            commands::tile_to_cells::run(&args)?;
+        }
+        Command::Tiles(args) => {
+            commands::tiles::run(&args)?;
+        }
     };
 
     Ok(())
@@ -0,0 +1,94 @@
+use crate::{tile::Tile, tileset::Tileset};
+use anyhow::{Context, Result as AnyResult};
+use rusqlite::{Connection, params};
+use std::{fs, io, path::Path};
+
+/// Write the tileset into a new `MBTiles` file.
+///
+/// Tiles are gzip-compressed, and their rows follow the TMS scheme (numbered
+/// from the south), as per the `MBTiles` specification. The file is
+/// overwritten if it already exists.
+pub fn write(path: &Path, tileset: &Tileset) -> AnyResult<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => {
+            return Err(err)
+                .with_context(|| format!("remove {}", path.display()));
+        }
+        _ => {}
+    }
+    let mut conn = Connection::open(path)
+        .with_context(|| format!("create {}", path.display()))?;
+    let tx = conn.transaction().context("start transaction")?;
+
+    tx.execute_batch(
+        "CREATE TABLE metadata (name TEXT, value TEXT);
+         CREATE TABLE tiles (
+             zoom_level INTEGER,
+             tile_column INTEGER,
+             tile_row INTEGER,
+             tile_data BLOB
+         );
+         CREATE UNIQUE INDEX tile_index
+             ON tiles (zoom_level, tile_column, tile_row);",
+    )
+    .context("create MBTiles tables")?;
+    insert_metadata(&tx, tileset).context("insert MBTiles metadata")?;
+    insert_tiles(&tx, tileset).context("insert MBTiles tiles")?;
+
+    tx.commit().context("commit MBTiles")
+}
+
+/// Fill the `metadata` table.
+fn insert_metadata(conn: &Connection, tileset: &Tileset) -> AnyResult<()> {
+    let bounds = tileset.bounds;
+    let (lng, lat, zoom) = tileset.center();
+    let json = serde_json::json!({
+        "vector_layers": tileset.vector_layers(),
+    });
+    let rows = [
+        ("name", tileset.layer.clone()),
+        ("format", "pbf".to_owned()),
+        ("type", "overlay".to_owned()),
+        ("minzoom", tileset.min_zoom.to_string()),
+        ("maxzoom", tileset.max_zoom.to_string()),
+        (
+            "bounds",
+            format!(
+                "{},{},{},{}",
+                bounds.min().x,
+                bounds.min().y,
+                bounds.max().x,
+                bounds.max().y
+            ),
+        ),
+        ("center", format!("{lng},{lat},{zoom}")),
+        ("json", json.to_string()),
+    ];
+
+    let mut stmt =
+        conn.prepare("INSERT INTO metadata (name, value) VALUES (?1, ?2)")?;
+    for (name, value) in rows {
+        stmt.execute(params![name, value])?;
+    }
+
+    Ok(())
+}
+
+/// Fill the `tiles` table.
+fn insert_tiles(conn: &Connection, tileset: &Tileset) -> AnyResult<()> {
+    let mut stmt = conn.prepare(
+        "INSERT INTO tiles (zoom_level, tile_column, tile_row, tile_data)
+         VALUES (?1, ?2, ?3, ?4)",
+    )?;
+    for (tile, data) in &tileset.tiles {
+        let row = Tile::count(tile.zoom) - 1 - tile.y;
+        stmt.execute(params![
+            tile.zoom,
+            tile.x,
+            row,
+            crate::tileset::gzip(data)?
+        ])?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,157 @@
+use anyhow::{Context, Result as AnyResult};
+use geo::{
+    Area, BooleanOps, BoundingRect,
+    orient::{Direction, Orient},
+};
+use geo_types::{Coord, Geometry, LineString, MultiPolygon, Polygon, Rect};
+use geozero::{
+    ToMvt,
+    mvt::{Message, TagsBuilder, Tile, TileValue, tile},
+};
+
+/// Size of a tile, in tile coordinates.
+pub const EXTENT: u32 = 4096;
+
+/// Size of the margin kept around the tiles, in tile coordinates.
+///
+/// Clipping polygons with a margin hides the seams between tiles.
+pub const BUFFER: f64 = 64.;
+
+/// A feature property value.
+pub enum Value {
+    Text(String),
+    Integer(u64),
+    Number(f64),
+}
+
+impl From<Value> for TileValue {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Text(value) => Self::Str(value),
+            Value::Integer(value) => Self::Uint(value),
+            Value::Number(value) => Self::Double(value),
+        }
+    }
+}
+
+/// A vector tile layer.
+pub struct Layer {
+    name: String,
+    features: Vec<tile::Feature>,
+    tags: TagsBuilder,
+}
+
+impl Layer {
+    /// Returns an empty layer.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            features: Vec::new(),
+            tags: TagsBuilder::new(),
+        }
+    }
+
+    /// Add a polygonal feature, in tile coordinates.
+    ///
+    /// The polygons are clipped to the tile (margin included) and snapped to
+    /// the tile grid. Features left empty are skipped.
+    pub fn add(
+        &mut self,
+        id: Option<u64>,
+        properties: Vec<(&str, Value)>,
+        polygons: &MultiPolygon,
+    ) -> AnyResult<()> {
+        let clipped = clip(polygons);
+        let polygons = quantize(clipped.as_ref().unwrap_or(polygons));
+        if polygons.0.is_empty() {
+            return Ok(());
+        }
+
+        let mut feature = Geometry::MultiPolygon(polygons)
+            .to_mvt_unscaled()
+            .context("encode MVT geometry")?;
+        feature.id = id;
+        for (key, value) in properties {
+            let (key, value) = self.tags.insert_ref(key, value.into());
+            feature.tags.extend([key, value]);
+        }
+        self.features.push(feature);
+
+        Ok(())
+    }
+
+    /// Returns true if the layer has no feature.
+    pub const fn is_empty(&self) -> bool {
+        self.features.is_empty()
+    }
+
+    /// Returns the encoded tile, made of this layer.
+    pub fn encode(self) -> Vec<u8> {
+        let (keys, values) = self.tags.into_tags();
+        Tile {
+            layers: vec![tile::Layer {
+                version: 2,
+                name: self.name,
+                features: self.features,
+                keys,
+                values: values.into_iter().map(Into::into).collect(),
+                extent: Some(EXTENT),
+            }],
+        }
+        .encode_to_vec()
+    }
+}
+
+/// Snap polygons to the integer grid.
+///
+/// Repeated vertices and degenerate rings are removed, and rings are oriented
+/// as per the MVT specification: exterior rings have a positive area in tile
+/// coordinates.
+pub fn quantize(polygons: &MultiPolygon) -> MultiPolygon {
+    polygons
+        .iter()
+        .filter_map(|polygon| {
+            let exterior = quantize_ring(polygon.exterior())?;
+            let interiors = polygon
+                .interiors()
+                .iter()
+                .filter_map(quantize_ring)
+                .collect();
+            Some(Polygon::new(exterior, interiors).orient(Direction::Default))
+        })
+        .collect()
+}
+
+/// Snap a ring to the integer grid, unless it becomes degenerate.
+fn quantize_ring(ring: &LineString) -> Option<LineString> {
+    let mut coords = ring
+        .coords()
+        .map(|coord| Coord {
+            x: coord.x.round(),
+            y: coord.y.round(),
+        })
+        .collect::<Vec<_>>();
+    coords.dedup();
+    let ring = LineString(coords);
+    let area = Polygon::new(ring.clone(), Vec::new()).unsigned_area();
+
+    (ring.0.len() > 3 && area > 0.).then_some(ring)
+}
+
+/// Clip polygons to the tile, margin included.
+///
+/// Returns `None` if the polygons are already inside.
+fn clip(polygons: &MultiPolygon) -> Option<MultiPolygon> {
+    let (min, max) = (-BUFFER, f64::from(EXTENT) + BUFFER);
+    let rect = polygons.bounding_rect()?;
+    if rect.min().x >= min
+        && rect.min().y >= min
+        && rect.max().x <= max
+        && rect.max().y <= max
+    {
+        return None;
+    }
+    let tile = Rect::new(Coord { x: min, y: min }, Coord { x: max, y: max });
+
+    Some(polygons.intersection(&tile.to_polygon()))
+}
@@ -0,0 +1,244 @@
+use crate::{tile::Tile, tileset::Tileset};
+use anyhow::{Context, Result as AnyResult};
+use std::{io::Write, path::Path};
+
+/// Size of the `PMTiles` header, in bytes.
+const HEADER_SIZE: usize = 127;
+
+/// Maximum size of the header and root directory, in bytes (16 KiB).
+const ROOT_SIZE: usize = 0x4000;
+
+/// Number of entries per leaf directory, at least.
+const LEAF_SIZE: usize = 4096;
+
+/// Compression code for gzip.
+const GZIP: u8 = 2;
+
+/// Tile type code for Mapbox Vector Tiles.
+const MVT: u8 = 1;
+
+/// A directory entry.
+struct Entry {
+    /// ID of the (first) tile.
+    tile_id: u64,
+    /// Offset of the data, from the start of its section.
+    offset: u64,
+    /// Length of the data.
+    length: u64,
+    /// Number of tiles sharing the data, or 0 for a leaf directory.
+    run_length: u64,
+}
+
+/// Write the tileset into a new `PMTiles` (v3) archive.
+///
+/// Tiles, metadata and directories are gzip-compressed, and tiles are
+/// clustered (stored in tile ID order). The file is overwritten if it already
+/// exists.
+pub fn write(path: &Path, tileset: &Tileset) -> AnyResult<()> {
+    let mut tiles = tileset
+        .tiles
+        .iter()
+        .map(|(&tile, data)| Ok((tile_id(tile), crate::tileset::gzip(data)?)))
+        .collect::<AnyResult<Vec<_>>>()?;
+    tiles.sort_unstable_by_key(|&(tile_id, _)| tile_id);
+
+    let mut entries = Vec::with_capacity(tiles.len());
+    let mut data = Vec::new();
+    for (tile_id, bytes) in tiles {
+        entries.push(Entry {
+            tile_id,
+            offset: len(&data),
+            length: len(&bytes),
+            run_length: 1,
+        });
+        data.extend(bytes);
+    }
+
+    let metadata = serde_json::json!({
+        "name": tileset.layer,
+        "vector_layers": tileset.vector_layers(),
+    });
+    let metadata = crate::tileset::gzip(metadata.to_string().as_bytes())?;
+    let (root, leaves) = directories(&entries)?;
+
+    let mut out = crate::io::create(path)?;
+    out.write_all(&header(tileset, &entries, &root, &metadata, &leaves, &data))
+        .and_then(|()| out.write_all(&root))
+        .and_then(|()| out.write_all(&metadata))
+        .and_then(|()| out.write_all(&leaves))
+        .and_then(|()| out.write_all(&data))
+        .and_then(|()| out.flush())
+        .with_context(|| format!("write {}", path.display()))
+}
+
+/// Returns the header of the archive.
+///
+/// Sections follow the header in this order: root directory, metadata, leaf
+/// directories and tile data.
+fn header(
+    tileset: &Tileset,
+    entries: &[Entry],
+    root: &[u8],
+    metadata: &[u8],
+    leaves: &[u8],
+    data: &[u8],
+) -> Vec<u8> {
+    let (lng, lat, zoom) = tileset.center();
+    let root_offset = HEADER_SIZE as u64;
+    let metadata_offset = root_offset + len(root);
+    let leaves_offset = metadata_offset + len(metadata);
+    let data_offset = leaves_offset + len(leaves);
+    let count = len(entries);
+
+    let mut header = Vec::with_capacity(HEADER_SIZE);
+    header.extend(b"PMTiles");
+    header.push(3);
+    for value in [
+        root_offset,
+        len(root),
+        metadata_offset,
+        len(metadata),
+        leaves_offset,
+        len(leaves),
+        data_offset,
+        len(data),
+        // Addressed tiles, tile entries and tile contents.
+        count,
+        count,
+        count,
+    ] {
+        header.extend(value.to_le_bytes());
+    }
+    // Clustered, internal and tile compressions, tile type and zoom range.
+    header.extend([1, GZIP, GZIP, MVT, tileset.min_zoom, tileset.max_zoom]);
+    for value in [
+        tileset.bounds.min().x,
+        tileset.bounds.min().y,
+        tileset.bounds.max().x,
+        tileset.bounds.max().y,
+    ] {
+        header.extend(e7(value).to_le_bytes());
+    }
+    header.push(zoom);
+    header.extend(e7(lng).to_le_bytes());
+    header.extend(e7(lat).to_le_bytes());
+
+    header
+}
+
+/// Returns the compressed root directory and leaf directories.
+///
+/// Entries are moved into leaf directories when the root directory doesn't
+/// fit right after the header.
+fn directories(entries: &[Entry]) -> AnyResult<(Vec<u8>, Vec<u8>)> {
+    let root = crate::tileset::gzip(&serialize(entries))?;
+    if root.len() <= ROOT_SIZE - HEADER_SIZE {
+        return Ok((root, Vec::new()));
+    }
+
+    let mut leaf_size = LEAF_SIZE;
+    loop {
+        let mut root_entries = Vec::new();
+        let mut leaves = Vec::new();
+        for chunk in entries.chunks(leaf_size) {
+            let leaf = crate::tileset::gzip(&serialize(chunk))?;
+            root_entries.push(Entry {
+                tile_id: chunk[0].tile_id,
+                offset: len(&leaves),
+                length: len(&leaf),
+                run_length: 0,
+            });
+            leaves.extend(leaf);
+        }
+        let root = crate::tileset::gzip(&serialize(&root_entries))?;
+        if root.len() <= ROOT_SIZE - HEADER_SIZE {
+            return Ok((root, leaves));
+        }
+        leaf_size *= 2;
+    }
+}
+
+/// Serialize directory entries.
+///
+/// Columns are written one after the other, as varints: tile IDs (as deltas),
+/// run lengths, lengths and offsets (0 when contiguous with the previous
+/// entry, offset + 1 otherwise).
+fn serialize(entries: &[Entry]) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    write_varint(&mut buffer, len(entries));
+    let mut previous_id = 0;
+    for entry in entries {
+        write_varint(&mut buffer, entry.tile_id - previous_id);
+        previous_id = entry.tile_id;
+    }
+    for entry in entries {
+        write_varint(&mut buffer, entry.run_length);
+    }
+    for entry in entries {
+        write_varint(&mut buffer, entry.length);
+    }
+    let mut next_offset = None;
+    for entry in entries {
+        let offset = if next_offset == Some(entry.offset) {
+            0
+        } else {
+            entry.offset + 1
+        };
+        write_varint(&mut buffer, offset);
+        next_offset = Some(entry.offset + entry.length);
+    }
+
+    buffer
+}
+
+/// Append an unsigned LEB128 integer.
+#[expect(
+    clippy::cast_possible_truncation,
+    reason = "value is masked to 7 bits"
+)]
+fn write_varint(buffer: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buffer.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+    buffer.push(value as u8);
+}
+
+/// Returns the ID of a tile: its position along the Hilbert curve at its zoom
+/// level, after the tiles of the lower zoom levels.
+fn tile_id(tile: Tile) -> u64 {
+    let size = u64::from(Tile::count(tile.zoom));
+    let (mut x, mut y) = (u64::from(tile.x), u64::from(tile.y));
+    let mut id = (size * size - 1) / 3;
+
+    let mut step = size / 2;
+    while step > 0 {
+        let rx = u64::from(x & step != 0);
+        let ry = u64::from(y & step != 0);
+        id += step * step * ((3 * rx) ^ ry);
+        if ry == 0 {
+            if rx == 1 {
+                x = size - 1 - x;
+                y = size - 1 - y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        step /= 2;
+    }
+
+    id
+}
+
+/// Returns a coordinate in units of 10⁻⁷ degrees.
+#[expect(
+    clippy::cast_possible_truncation,
+    reason = "coordinates are within ±180°"
+)]
+fn e7(degrees: f64) -> i32 {
+    (degrees * 1e7).round() as i32
+}
+
+/// Returns the length of a slice, as an integer for the archive.
+const fn len<T>(slice: &[T]) -> u64 {
+    slice.len() as u64
+}
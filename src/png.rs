@@ -0,0 +1,51 @@
+use crate::{
+    raster::Canvas,
+    render::{Cell, ColorScale, Viewport},
+};
+use anyhow::{Context, Result as AnyResult};
+use geo::MapCoords;
+use geo_types::MultiPolygon;
+use std::io::Write;
+
+/// Write the cells as a PNG image, with a transparent background.
+pub fn write(
+    out: impl Write,
+    cells: &[Cell],
+    shapes: &[MultiPolygon],
+    colors: &ColorScale,
+    viewport: &Viewport,
+    (width, height): (u32, u32),
+) -> AnyResult<()> {
+    let mut canvas = Canvas::new(
+        usize::try_from(width).context("image too wide")?,
+        usize::try_from(height).context("image too high")?,
+    );
+    for (cell, shape) in cells.iter().zip(shapes) {
+        let color = colors.color(cell.value);
+        for polygon in shape {
+            canvas.fill(
+                &polygon.map_coords(|coord| viewport.pixel(coord)),
+                color,
+            );
+        }
+    }
+
+    let mut encoder = ::png::Encoder::new(out, width, height);
+    encoder.set_color(::png::ColorType::Rgba);
+    encoder.set_depth(::png::BitDepth::Eight);
+    let data = canvas
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            pixel.map_or([0; 4], |color| {
+                [color.red, color.green, color.blue, u8::MAX]
+            })
+        })
+        .collect::<Vec<_>>();
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&data))
+        .context("write PNG")?;
+
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use clap::ValueEnum;
+use h3o::geom::ContainmentMode;
+
+/// Polyfill mode.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum Mode {
+    ContainsCentroid,
+    ContainsBoundary,
+    IntersectsBoundary,
+    Covers,
+}
+
+impl From<Mode> for ContainmentMode {
+    fn from(value: Mode) -> Self {
+        match value {
+            Mode::ContainsCentroid => Self::ContainsCentroid,
+            Mode::ContainsBoundary => Self::ContainsBoundary,
+            Mode::IntersectsBoundary => Self::IntersectsBoundary,
+            Mode::Covers => Self::Covers,
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use crate::render::Rgb;
+use geo_types::{Coord, LineString, Polygon};
+
+/// A raster image, whose pixels are either empty or colored.
+pub struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<Option<Rgb>>,
+}
+
+impl Canvas {
+    /// Returns an empty canvas.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![None; width * height],
+        }
+    }
+
+    /// Returns the width of the canvas, in pixels.
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the pixels, row by row from the top.
+    pub fn pixels(&self) -> &[Option<Rgb>] {
+        &self.pixels
+    }
+
+    /// Returns the color of a pixel, if any.
+    ///
+    /// Pixels outside of the canvas are empty.
+    pub fn get(&self, x: usize, y: usize) -> Option<Rgb> {
+        (x < self.width && y < self.height)
+            .then(|| self.pixels[y * self.width + x])
+            .flatten()
+    }
+
+    /// Fill a polygon, in pixel coordinates.
+    ///
+    /// Pixels are filled if their center is inside the polygon (even-odd
+    /// rule).
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss,
+        reason = "pixel coordinates are clamped to the canvas"
+    )]
+    pub fn fill(&mut self, polygon: &Polygon, color: Rgb) {
+        let rings = std::iter::once(polygon.exterior())
+            .chain(polygon.interiors())
+            .collect::<Vec<_>>();
+        let (min_y, max_y) =
+            rings.iter().flat_map(|ring| ring.coords()).fold(
+                (f64::INFINITY, f64::NEG_INFINITY),
+                |(min, max), coord| (min.min(coord.y), max.max(coord.y)),
+            );
+        if min_y > max_y {
+            return;
+        }
+        let first_row = (min_y - 0.5).ceil().max(0.) as usize;
+        let last_row = (max_y - 0.5).floor().min(self.height as f64 - 1.);
+        if last_row < 0. {
+            return;
+        }
+
+        let mut crossings = Vec::new();
+        for row in first_row..=(last_row as usize) {
+            let y = row as f64 + 0.5;
+            crossings.clear();
+            crossings.extend(
+                rings
+                    .iter()
+                    .flat_map(|ring| ring.lines())
+                    .filter_map(|line| {
+                        let (start, end) = (line.start, line.end);
+                        ((start.y <= y) != (end.y <= y)).then(|| {
+                            ((y - start.y) / (end.y - start.y))
+                                .mul_add(end.x - start.x, start.x)
+                        })
+                    }),
+            );
+            crossings.sort_by(f64::total_cmp);
+
+            for span in crossings.chunks_exact(2) {
+                let first = (span[0] - 0.5).ceil().max(0.);
+                let last = (span[1] - 0.5).floor().min(self.width as f64 - 1.);
+                if first > last {
+                    continue;
+                }
+                let offset = row * self.width;
+                self.pixels[offset + first as usize..=offset + last as usize]
+                    .fill(Some(color));
+            }
+        }
+    }
+
+    /// Draw the outline of a polygon, in pixel coordinates.
+    pub fn stroke(&mut self, polygon: &Polygon, color: Rgb) {
+        for ring in
+            std::iter::once(polygon.exterior()).chain(polygon.interiors())
+        {
+            self.stroke_ring(ring, color);
+        }
+    }
+
+    /// Draw the segments of a ring.
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "pixels outside of the canvas are skipped"
+    )]
+    fn stroke_ring(&mut self, ring: &LineString, color: Rgb) {
+        for line in ring.lines() {
+            let Coord { x: dx, y: dy } = line.delta();
+            let steps = dx.abs().max(dy.abs()).ceil().max(1.);
+            let mut step = 0.;
+            while step <= steps {
+                let ratio = step / steps;
+                let x = ratio.mul_add(dx, line.start.x).floor();
+                let y = ratio.mul_add(dy, line.start.y).floor();
+                if x >= 0. && y >= 0. {
+                    let (x, y) = (x as usize, y as usize);
+                    if x < self.width && y < self.height {
+                        self.pixels[y * self.width + x] = Some(color);
+                    }
+                }
+                step += 1.;
+            }
+        }
+    }
+}
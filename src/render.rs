@@ -0,0 +1,436 @@
+use anyhow::{Context, Result as AnyResult};
+use clap::ValueEnum;
+use geo::{BoundingRect, MapCoords, Translate};
+use geo_types::{Coord, MultiPolygon, Rect};
+use h3o::CellIndex;
+use std::{fmt, num::NonZeroUsize};
+
+/// Map rendering options.
+#[derive(clap::Args, Debug, Copy, Clone)]
+#[command(next_help_heading = "Map options")]
+pub struct MapArgs {
+    /// Input field holding the value of each cell, counted from 1 after the
+    /// cell index.
+    ///
+    /// Cells are colored from their value, along the color ramp.
+    #[arg(long)]
+    pub value_field: Option<NonZeroUsize>,
+
+    /// Map projection.
+    #[arg(long, value_enum, default_value_t = Projection::Equirectangular)]
+    pub projection: Projection,
+
+    /// Color ramp.
+    #[arg(long, value_enum, default_value_t = Ramp::Viridis)]
+    pub ramp: Ramp,
+}
+
+/// A map projection.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum Projection {
+    /// Longitude and latitude as plane coordinates (plate carrée).
+    Equirectangular,
+    /// Web Mercator, as used by web maps (latitudes are clamped to ±85.05°).
+    WebMercator,
+}
+
+impl Projection {
+    /// Project a longitude/latitude coordinate on the plane.
+    ///
+    /// Both axes are in degrees, with latitudes going up.
+    pub fn project(self, coord: Coord) -> Coord {
+        match self {
+            Self::Equirectangular => coord,
+            Self::WebMercator => {
+                let lat = coord
+                    .y
+                    .clamp(-crate::tile::MAX_LAT, crate::tile::MAX_LAT)
+                    .to_radians();
+                Coord {
+                    x: coord.x,
+                    y: (lat / 2. + std::f64::consts::FRAC_PI_4)
+                        .tan()
+                        .ln()
+                        .to_degrees(),
+                }
+            }
+        }
+    }
+
+    /// Returns the longitude/latitude of a projected coordinate.
+    pub fn unproject(self, coord: Coord) -> Coord {
+        match self {
+            Self::Equirectangular => coord,
+            Self::WebMercator => Coord {
+                x: coord.x,
+                y: coord.y.to_radians().sinh().atan().to_degrees(),
+            },
+        }
+    }
+}
+
+/// A color ramp.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum Ramp {
+    /// Perceptually uniform, from dark purple to yellow.
+    Viridis,
+    /// Perceptually uniform, from black to light yellow.
+    Magma,
+    /// Sequential, from white to dark blue.
+    Blues,
+}
+
+impl Ramp {
+    /// Returns the color stops of the ramp, evenly spaced.
+    pub const fn stops(self) -> [Rgb; 5] {
+        match self {
+            Self::Viridis => [
+                Rgb::new(0x44, 0x01, 0x54),
+                Rgb::new(0x3b, 0x52, 0x8b),
+                Rgb::new(0x21, 0x91, 0x8c),
+                Rgb::new(0x5e, 0xc9, 0x62),
+                Rgb::new(0xfd, 0xe7, 0x25),
+            ],
+            Self::Magma => [
+                Rgb::new(0x00, 0x00, 0x04),
+                Rgb::new(0x51, 0x12, 0x7c),
+                Rgb::new(0xb7, 0x37, 0x79),
+                Rgb::new(0xfc, 0x89, 0x61),
+                Rgb::new(0xfc, 0xfd, 0xbf),
+            ],
+            Self::Blues => [
+                Rgb::new(0xf7, 0xfb, 0xff),
+                Rgb::new(0xc6, 0xdb, 0xef),
+                Rgb::new(0x6b, 0xae, 0xd6),
+                Rgb::new(0x21, 0x71, 0xb5),
+                Rgb::new(0x08, 0x30, 0x6b),
+            ],
+        }
+    }
+
+    /// Returns the color at `t` along the ramp, from 0 to 1.
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "t is in [0, 1]"
+    )]
+    pub fn color(self, t: f64) -> Rgb {
+        let stops = self.stops();
+        // Position along the 4 intervals between the 5 stops.
+        let position = t.clamp(0., 1.) * 4.;
+        let index = position.floor().min(3.);
+        let ratio = position - index;
+        let (from, to) = (stops[index as usize], stops[index as usize + 1]);
+        let mix = |from: u8, to: u8| {
+            ratio
+                .mul_add(f64::from(to) - f64::from(from), f64::from(from))
+                .round() as u8
+        };
+
+        Rgb::new(
+            mix(from.red, to.red),
+            mix(from.green, to.green),
+            mix(from.blue, to.blue),
+        )
+    }
+}
+
+/// An RGB color.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Rgb {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+
+impl Rgb {
+    /// Returns a new color.
+    pub const fn new(red: u8, green: u8, blue: u8) -> Self {
+        Self { red, green, blue }
+    }
+}
+
+impl fmt::Display for Rgb {
+    /// Format the color as an hex triplet (`#rrggbb`).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.red, self.green, self.blue)
+    }
+}
+
+/// A cell to render, with its value if any.
+#[derive(Debug, Copy, Clone)]
+pub struct Cell {
+    pub index: CellIndex,
+    pub value: Option<f64>,
+}
+
+impl Cell {
+    /// Returns the boundary of the cell, split along the antimeridian.
+    pub fn boundary(self) -> MultiPolygon {
+        crate::antimeridian::cell(self.index)
+    }
+}
+
+/// Projects cells on the map plane.
+///
+/// Sets lying across the antimeridian are drawn with longitudes in the
+/// [0°, 360°] range instead, to keep them contiguous.
+#[derive(Debug, Copy, Clone)]
+pub struct Map {
+    projection: Projection,
+    /// Western hemisphere shifted eastward, past the antimeridian.
+    shifted: bool,
+}
+
+impl Map {
+    /// Returns the map best fitting the cell boundaries, with their projected
+    /// extent.
+    ///
+    /// The extent is `None` if there are no cells.
+    pub fn new(
+        boundaries: &[MultiPolygon],
+        projection: Projection,
+    ) -> (Self, Option<Rect>) {
+        let (mut extent, mut shifted_extent) = (None, None);
+        for rect in boundaries
+            .iter()
+            .flatten()
+            .filter_map(BoundingRect::bounding_rect)
+        {
+            let shifted = if rect.max().x <= 0. {
+                rect.translate(360., 0.)
+            } else {
+                rect
+            };
+            extent = Some(union(extent, rect));
+            shifted_extent = Some(union(shifted_extent, shifted));
+        }
+        let shifted = match (extent, shifted_extent) {
+            (Some(extent), Some(shifted)) => shifted.width() < extent.width(),
+            _ => false,
+        };
+        let map = Self {
+            projection,
+            shifted,
+        };
+        let extent = if shifted { shifted_extent } else { extent };
+
+        (map, extent.map(|rect| map.project_rect(rect)))
+    }
+
+    /// Returns the projected shape of a cell boundary.
+    pub fn shape(self, boundary: MultiPolygon) -> MultiPolygon {
+        boundary
+            .into_iter()
+            .map(|polygon| {
+                let shift = self.shifted
+                    && polygon.exterior().coords().all(|coord| coord.x <= 0.);
+                polygon.map_coords(|coord| {
+                    self.projection.project(Coord {
+                        x: if shift { coord.x + 360. } else { coord.x },
+                        ..coord
+                    })
+                })
+            })
+            .collect()
+    }
+
+    /// Project a longitude/latitude coordinate on the map.
+    pub fn project(self, coord: Coord) -> Coord {
+        self.projection.project(Coord {
+            x: if self.shifted && coord.x < 0. {
+                coord.x + 360.
+            } else {
+                coord.x
+            },
+            ..coord
+        })
+    }
+
+    /// Returns the longitude/latitude of a projected coordinate.
+    pub fn unproject(self, coord: Coord) -> Coord {
+        let coord = self.projection.unproject(coord);
+        Coord {
+            x: if coord.x > 180. {
+                coord.x - 360.
+            } else {
+                coord.x
+            },
+            ..coord
+        }
+    }
+
+    /// Project a longitude/latitude rectangle, already shifted if needed.
+    fn project_rect(self, rect: Rect) -> Rect {
+        Rect::new(
+            self.projection.project(rect.min()),
+            self.projection.project(rect.max()),
+        )
+    }
+}
+
+/// Returns the map best fitting the cells, with their projected shapes (in
+/// the cells' order) and extent.
+///
+/// Each boundary is computed and projected once, to be reused by every
+/// drawing pass.
+pub fn project(
+    cells: &[Cell],
+    projection: Projection,
+) -> (Map, Vec<MultiPolygon>, Option<Rect>) {
+    let boundaries =
+        cells.iter().map(|cell| cell.boundary()).collect::<Vec<_>>();
+    let (map, extent) = Map::new(&boundaries, projection);
+    let shapes = boundaries
+        .into_iter()
+        .map(|boundary| map.shape(boundary))
+        .collect();
+
+    (map, shapes, extent)
+}
+
+/// Read the cells to render, with their value if a value field is set.
+pub fn read_cells(
+    index: Option<CellIndex>,
+    field: Option<NonZeroUsize>,
+) -> AnyResult<Vec<Cell>> {
+    crate::utils::get_cell_records(index)
+        .map(|record| {
+            let (index, fields) = record?;
+            let value = field
+                .map(|field| {
+                    fields
+                        .get(field.get() - 1)
+                        .with_context(|| {
+                            format!("missing value field for {index}")
+                        })?
+                        .parse::<f64>()
+                        .with_context(|| format!("invalid value for {index}"))
+                })
+                .transpose()?;
+            Ok(Cell { index, value })
+        })
+        .collect()
+}
+
+/// Returns the smallest rectangle containing `rect` and `acc`, if any.
+fn union(acc: Option<Rect>, rect: Rect) -> Rect {
+    acc.map_or(rect, |acc| {
+        Rect::new(
+            (acc.min().x.min(rect.min().x), acc.min().y.min(rect.min().y)),
+            (acc.max().x.max(rect.max().x), acc.max().y.max(rect.max().y)),
+        )
+    })
+}
+
+/// Maps the cells' values to colors along a ramp.
+#[derive(Debug, Copy, Clone)]
+pub struct ColorScale {
+    ramp: Ramp,
+    range: Option<(f64, f64)>,
+}
+
+impl ColorScale {
+    /// Returns a color scale spanning the cells' values.
+    pub fn new(cells: &[Cell], ramp: Ramp) -> Self {
+        let range = cells.iter().filter_map(|cell| cell.value).fold(
+            None,
+            |range: Option<(f64, f64)>, value| {
+                Some(range.map_or((value, value), |(min, max)| {
+                    (min.min(value), max.max(value))
+                }))
+            },
+        );
+
+        Self { ramp, range }
+    }
+
+    /// Returns the color ramp.
+    pub const fn ramp(&self) -> Ramp {
+        self.ramp
+    }
+
+    /// Returns the range of values, if any.
+    pub const fn range(&self) -> Option<(f64, f64)> {
+        self.range
+    }
+
+    /// Returns the color of a value.
+    ///
+    /// Cells without value get the middle color of the ramp.
+    pub fn color(&self, value: Option<f64>) -> Rgb {
+        let t = match (value, self.range) {
+            (Some(value), Some((min, max))) if max > min => {
+                (value - min) / (max - min)
+            }
+            (Some(_), _) => 1.,
+            (None, _) => 0.5,
+        };
+        self.ramp.color(t)
+    }
+}
+
+/// Maps projected coordinates to pixels.
+#[derive(Debug, Copy, Clone)]
+pub struct Viewport {
+    /// Top-left corner of the extent, in projected coordinates.
+    origin: Coord,
+    /// Pixels per projected unit.
+    scale: f64,
+    /// Offset centering the extent, in pixels.
+    offset: Coord,
+}
+
+impl Viewport {
+    /// Fit the extent in a `width` × `height` area, keeping its aspect ratio.
+    ///
+    /// `margin` pixels are left empty on every side.
+    pub fn fit(extent: Rect, width: f64, height: f64, margin: f64) -> Self {
+        let (inner_width, inner_height) = (
+            2_f64.mul_add(-margin, width),
+            2_f64.mul_add(-margin, height),
+        );
+        let scale = (inner_width / extent.width().max(f64::EPSILON))
+            .min(inner_height / extent.height().max(f64::EPSILON));
+
+        Self {
+            origin: Coord {
+                x: extent.min().x,
+                y: extent.max().y,
+            },
+            scale,
+            offset: Coord {
+                x: extent.width().mul_add(-scale, width) / 2.,
+                y: extent.height().mul_add(-scale, height) / 2.,
+            },
+        }
+    }
+
+    /// Returns the height of a `width` pixels wide image fitting the extent.
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "height is clamped to a small positive range"
+    )]
+    pub fn height_for(extent: Rect, width: u32, margin: f64) -> u32 {
+        let inner_width = 2_f64.mul_add(-margin, f64::from(width));
+        let ratio = extent.height() / extent.width().max(f64::EPSILON);
+        2_f64
+            .mul_add(margin, inner_width * ratio)
+            .round()
+            .clamp(1., f64::from(width) * 4.) as u32
+    }
+
+    /// Returns the number of pixels per projected unit.
+    pub const fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// Returns the pixel position of a projected coordinate.
+    pub fn pixel(&self, coord: Coord) -> Coord {
+        Coord {
+            x: (coord.x - self.origin.x).mul_add(self.scale, self.offset.x),
+            y: (self.origin.y - coord.y).mul_add(self.scale, self.offset.y),
+        }
+    }
+}
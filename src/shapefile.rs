@@ -0,0 +1,101 @@
+use anyhow::{Context, Result as AnyResult, ensure};
+use geojson::{JsonObject, JsonValue};
+use shapefile::{
+    Reader, Shape,
+    dbase::{self, FieldValue},
+};
+use std::{fs, io, path::Path};
+
+/// Read every record of a shapefile, as a geometry and its DBF attributes.
+///
+/// Records with a null shape are skipped.
+pub fn read(
+    path: &Path,
+) -> AnyResult<Vec<(geo_types::Geometry<f64>, JsonObject)>> {
+    check_projection(path)?;
+
+    let mut reader = Reader::from_path(path)
+        .with_context(|| format!("open shapefile {}", path.display()))?;
+    reader
+        .iter_shapes_and_records()
+        .enumerate()
+        .filter_map(|(i, item)| {
+            let (shape, record) = match item {
+                Ok((Shape::NullShape, _)) => return None,
+                Ok(item) => item,
+                Err(err) => {
+                    return Some(
+                        Err(err).with_context(|| format!("read record #{i}")),
+                    );
+                }
+            };
+            let geometry = geo_types::Geometry::try_from(shape)
+                .with_context(|| format!("invalid geometry in record #{i}"));
+            let properties = record
+                .into_iter()
+                .map(|(name, value)| (name, to_json(value)))
+                .collect::<JsonObject>();
+
+            Some(geometry.map(|geometry| (geometry, properties)))
+        })
+        .collect()
+}
+
+/// Ensure that the shapefile coordinates are WGS84 longitude/latitude.
+///
+/// A missing `.prj` is assumed to be WGS84, as per the usual convention.
+fn check_projection(path: &Path) -> AnyResult<()> {
+    let prj = path.with_extension("prj");
+    let wkt = match fs::read_to_string(&prj) {
+        Ok(wkt) => wkt,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => {
+            return Err(err).with_context(|| format!("read {}", prj.display()));
+        }
+    };
+    let wkt = wkt.trim();
+
+    // Projected CRS (`PROJCS`) are rejected even if based on WGS84 (e.g. UTM).
+    let is_geographic =
+        wkt.starts_with("GEOGCS[") || wkt.starts_with("GEOGCRS[");
+    let is_wgs84 = ["WGS_1984", "WGS 84", "WGS84"]
+        .iter()
+        .any(|name| wkt.contains(name));
+    ensure!(
+        is_geographic && is_wgs84,
+        "unsupported coordinate system in {} (expected WGS84): {wkt}",
+        prj.display()
+    );
+
+    Ok(())
+}
+
+/// Convert a DBF field value into its JSON equivalent.
+fn to_json(value: FieldValue) -> JsonValue {
+    match value {
+        FieldValue::Character(value) => value.into(),
+        FieldValue::Numeric(value) => value.into(),
+        FieldValue::Logical(value) => value.into(),
+        FieldValue::Date(value) => value.map(format_date).into(),
+        FieldValue::Float(value) => value.map(f64::from).into(),
+        FieldValue::Integer(value) => value.into(),
+        FieldValue::Currency(value) | FieldValue::Double(value) => value.into(),
+        FieldValue::DateTime(value) => {
+            let time = value.time();
+            format!(
+                "{}T{:02}:{:02}:{:02}",
+                format_date(value.date()),
+                time.hours(),
+                time.minutes(),
+                time.seconds()
+            )
+            .into()
+        }
+        FieldValue::Memo(value) => value.into(),
+    }
+}
+
+/// Format a DBF date as ISO 8601.
+fn format_date(date: dbase::Date) -> String {
+    format!("{:04}-{:02}-{:02}", date.year(), date.month(), date.day())
+}
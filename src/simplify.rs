@@ -0,0 +1,309 @@
+use clap::ValueEnum;
+use geo::{
+    BoundingRect, ChaikinSmoothing, MapCoords, Relate, Simplify,
+    SimplifyVwPreserve, Validation, coordinate_position::CoordPos,
+    dimensions::Dimensions,
+};
+use geo_types::{Coord, LineString, MultiPolygon, Polygon};
+use std::collections::{HashMap, HashSet};
+
+/// Mean radius of the Earth, in meters.
+const EARTH_RADIUS_M: f64 = 6_371_007.180_918_475;
+
+/// A line simplification algorithm.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum Algorithm {
+    /// Ramer–Douglas–Peucker: drop vertices closer than the tolerance to the
+    /// simplified line.
+    DouglasPeucker,
+    /// Visvalingam–Whyatt: drop vertices forming triangles smaller than the
+    /// squared tolerance.
+    Visvalingam,
+}
+
+/// A vertex, compared by its exact coordinates.
+type Vertex = (u64, u64);
+
+/// An arc, identified by its first two vertexes once oriented canonically.
+type ArcKey = (Vertex, Vertex);
+
+/// Simplify the polygons of several groups, with a tolerance in meters.
+///
+/// The rings are split into arcs between the vertexes where three or more
+/// boundaries meet, and each arc is simplified once, then reused on every side:
+/// adjacent polygons, within a group or across groups, still share their
+/// boundary. Arcs making up rings that would collapse are kept as is, and if
+/// the simplification would make a polygon invalid (e.g. self-intersecting) or
+/// polygons overlap, the whole set is kept as is.
+pub fn simplify(
+    groups: Vec<MultiPolygon>,
+    algorithm: Algorithm,
+    tolerance: f64,
+) -> Vec<MultiPolygon> {
+    let nodes = nodes(&groups);
+    let arcs = groups
+        .iter()
+        .map(|polygons| {
+            polygons
+                .iter()
+                .map(|polygon| {
+                    std::iter::once(polygon.exterior())
+                        .chain(polygon.interiors())
+                        .map(|ring| split(ring, &nodes))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut simplified = HashMap::new();
+    for arc in arcs.iter().flatten().flatten().flatten() {
+        let (key, canonical) = canonical(arc);
+        simplified.entry(key).or_insert_with(|| {
+            let origin = canonical.0[0];
+            in_meters(&canonical, origin, |projected| match algorithm {
+                Algorithm::DouglasPeucker => projected.simplify(tolerance),
+                Algorithm::Visvalingam => {
+                    projected.simplify_vw_preserve(tolerance * tolerance)
+                }
+            })
+        });
+    }
+    // Restore the arcs of collapsed rings, until none collapses.
+    let mut collapsed = true;
+    while collapsed {
+        collapsed = false;
+        for ring in arcs.iter().flatten().flatten() {
+            if join(ring, &simplified).0.len() >= 4 {
+                continue;
+            }
+            for arc in ring {
+                let (key, canonical) = canonical(arc);
+                if simplified.get(&key) != Some(&canonical) {
+                    simplified.insert(key, canonical);
+                    collapsed = true;
+                }
+            }
+        }
+    }
+
+    let result = arcs
+        .iter()
+        .map(|polygons| {
+            polygons
+                .iter()
+                .map(|rings| {
+                    let mut rings =
+                        rings.iter().map(|ring| join(ring, &simplified));
+                    let exterior =
+                        rings.next().unwrap_or_else(|| LineString(Vec::new()));
+                    Polygon::new(exterior, rings.collect())
+                })
+                .collect::<MultiPolygon>()
+        })
+        .collect::<Vec<_>>();
+    if is_valid(&result) { result } else { groups }
+}
+
+/// Smooth polygons with the given number of Chaikin iterations.
+///
+/// The rings are first rotated to start from their lowest vertex, so that the
+/// result doesn't depend on the starting vertex of the input rings.
+pub fn smooth(polygons: MultiPolygon, iterations: usize) -> MultiPolygon {
+    polygons
+        .into_iter()
+        .map(|polygon| {
+            let polygon = Polygon::new(
+                rotate(polygon.exterior()),
+                polygon.interiors().iter().map(rotate).collect(),
+            );
+            let Some(&origin) = polygon.exterior().0.first() else {
+                return polygon;
+            };
+            in_meters(&polygon, origin, |projected| {
+                projected.chaikin_smoothing(iterations)
+            })
+        })
+        .collect()
+}
+
+/// Apply `f` on the geometry projected in meters.
+///
+/// The geometry is projected on a local equirectangular plane centered on the
+/// origin, which keeps geometries crossing the antimeridian contiguous.
+fn in_meters<G>(geometry: &G, origin: Coord, f: impl FnOnce(G) -> G) -> G
+where
+    G: MapCoords<f64, f64, Output = G>,
+{
+    let scale = EARTH_RADIUS_M * origin.y.to_radians().cos();
+
+    let projected = geometry.map_coords(|coord| Coord {
+        x: wrap(coord.x - origin.x).to_radians() * scale,
+        y: (coord.y - origin.y).to_radians() * EARTH_RADIUS_M,
+    });
+    f(projected).map_coords(|coord| Coord {
+        x: wrap(origin.x + (coord.x / scale).to_degrees()),
+        y: origin.y + (coord.y / EARTH_RADIUS_M).to_degrees(),
+    })
+}
+
+/// Returns the vertex of a coordinate.
+const fn vertex(coord: Coord) -> Vertex {
+    (coord.x.to_bits(), coord.y.to_bits())
+}
+
+/// Returns the vertexes where the rings don't run along a single boundary,
+/// i.e. those without exactly two distinct neighbors.
+fn nodes(groups: &[MultiPolygon]) -> HashSet<Vertex> {
+    let mut neighbors = HashMap::<Vertex, Vec<Vertex>>::new();
+    let rings = groups.iter().flatten().flat_map(|polygon| {
+        std::iter::once(polygon.exterior()).chain(polygon.interiors())
+    });
+    for ring in rings {
+        let Some((_, vertexes)) = ring.0.split_last() else {
+            continue;
+        };
+        let count = vertexes.len();
+        for (i, &coord) in vertexes.iter().enumerate() {
+            let entry = neighbors.entry(vertex(coord)).or_default();
+            for neighbor in
+                [vertexes[(i + count - 1) % count], vertexes[(i + 1) % count]]
+            {
+                let neighbor = vertex(neighbor);
+                if !entry.contains(&neighbor) {
+                    entry.push(neighbor);
+                }
+            }
+        }
+    }
+
+    neighbors
+        .into_iter()
+        .filter_map(|(vertex, neighbors)| {
+            (neighbors.len() != 2).then_some(vertex)
+        })
+        .collect()
+}
+
+/// Split a closed ring into arcs, between its nodes.
+///
+/// A ring without nodes is a single closed arc, starting from its lowest
+/// vertex.
+fn split(ring: &LineString, nodes: &HashSet<Vertex>) -> Vec<LineString> {
+    let Some((_, vertexes)) = ring.0.split_last() else {
+        return Vec::new();
+    };
+    let Some(start) = vertexes
+        .iter()
+        .position(|&coord| nodes.contains(&vertex(coord)))
+    else {
+        return vec![rotate(ring)];
+    };
+
+    let mut arcs = Vec::new();
+    let mut arc = vec![vertexes[start]];
+    for &coord in vertexes[start + 1..].iter().chain(&vertexes[..=start]) {
+        arc.push(coord);
+        if nodes.contains(&vertex(coord)) {
+            arcs.push(LineString(std::mem::replace(&mut arc, vec![coord])));
+        }
+    }
+
+    arcs
+}
+
+/// Returns the key of an arc, and the arc in canonical orientation.
+///
+/// The two sides of a shared boundary run along it in opposite directions:
+/// the canonical orientation is the one with the smallest first two vertexes.
+fn canonical(arc: &LineString) -> (ArcKey, LineString) {
+    let coords = &arc.0;
+    let forward = (vertex(coords[0]), vertex(coords[1]));
+    let backward = (
+        vertex(coords[coords.len() - 1]),
+        vertex(coords[coords.len() - 2]),
+    );
+    if backward < forward {
+        (backward, LineString(coords.iter().rev().copied().collect()))
+    } else {
+        (forward, arc.clone())
+    }
+}
+
+/// Join the simplified arcs of a ring back into a closed ring.
+fn join(
+    ring: &[LineString],
+    simplified: &HashMap<ArcKey, LineString>,
+) -> LineString {
+    let mut coords = Vec::<Coord>::new();
+    for arc in ring {
+        let (key, _) = canonical(arc);
+        let Some(simplified) = simplified.get(&key) else {
+            continue;
+        };
+        let arc = if key.0 == vertex(arc.0[0]) && key.1 == vertex(arc.0[1]) {
+            simplified.0.clone()
+        } else {
+            simplified.0.iter().rev().copied().collect()
+        };
+        let skip = usize::from(!coords.is_empty());
+        coords.extend(arc.into_iter().skip(skip));
+    }
+
+    LineString(coords)
+}
+
+/// Returns whether every polygon is valid, and no two polygons overlap.
+fn is_valid(groups: &[MultiPolygon]) -> bool {
+    if !groups.iter().flatten().all(Validation::is_valid) {
+        return false;
+    }
+    let mut polygons = groups
+        .iter()
+        .flatten()
+        .filter_map(|polygon| Some((polygon.bounding_rect()?, polygon)))
+        .collect::<Vec<_>>();
+    polygons
+        .sort_unstable_by(|lhs, rhs| lhs.0.min().x.total_cmp(&rhs.0.min().x));
+
+    // Sweep along the longitudes, comparing polygons whose bounds intersect.
+    polygons.iter().enumerate().all(|(i, &(rect, polygon))| {
+        polygons[i + 1..]
+            .iter()
+            .take_while(|&&(other, _)| other.min().x <= rect.max().x)
+            .filter(|&&(other, _)| {
+                other.min().y <= rect.max().y && rect.min().y <= other.max().y
+            })
+            .all(|&(_, other)| {
+                polygon
+                    .relate(other)
+                    .get(CoordPos::Inside, CoordPos::Inside)
+                    != Dimensions::TwoDimensional
+            })
+    })
+}
+
+/// Rotate a closed ring to start from its lowest vertex (by longitude, then
+/// latitude).
+fn rotate(ring: &LineString) -> LineString {
+    let Some((_, vertexes)) = ring.0.split_last() else {
+        return ring.clone();
+    };
+    let start = vertexes
+        .iter()
+        .enumerate()
+        .min_by(|&(_, lhs), &(_, rhs)| {
+            lhs.x.total_cmp(&rhs.x).then(lhs.y.total_cmp(&rhs.y))
+        })
+        .map_or(0, |(i, _)| i);
+    let mut coords = vertexes.to_vec();
+    coords.rotate_left(start);
+    coords.extend(coords.first().copied());
+
+    LineString(coords)
+}
+
+/// Wrap a longitude into the [-180°, 180°) range.
+fn wrap(lng: f64) -> f64 {
+    (lng + 180.).rem_euclid(360.) - 180.
+}
@@ -0,0 +1,199 @@
+use crate::render::{Cell, ColorScale, Map, Viewport};
+use anyhow::{Context, Result as AnyResult};
+use geo::BoundingRect;
+use geo_types::{Coord, MultiPolygon};
+use h3o::LatLng;
+use std::io::Write;
+
+/// Stroke color of the cell outlines.
+const STROKE: &str = "#333333";
+
+/// Width and height of the legend color bar, in pixels.
+const LEGEND_SIZE: (f64, f64) = (160., 12.);
+
+/// SVG document options.
+#[derive(Debug, Copy, Clone)]
+pub struct Options {
+    /// Image width, in pixels.
+    pub width: u32,
+    /// Image height, in pixels.
+    pub height: u32,
+    /// Label each cell with its index.
+    pub labels: bool,
+    /// Add a legend.
+    pub legend: bool,
+}
+
+/// Write the cells as a standalone SVG document.
+pub fn write(
+    mut out: impl Write,
+    cells: &[Cell],
+    shapes: &[MultiPolygon],
+    map: Map,
+    colors: &ColorScale,
+    viewport: &Viewport,
+    options: Options,
+) -> AnyResult<()> {
+    let (width, height) = (options.width, options.height);
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+    )
+    .context("write SVG header")?;
+
+    writeln!(
+        out,
+        r#"<g stroke="{STROKE}" stroke-width="0.5" stroke-linejoin="round">"#
+    )
+    .context("write cells")?;
+    for (cell, shape) in cells.iter().zip(shapes) {
+        let path = path(shape, viewport);
+        let title = cell.value.map_or_else(
+            || cell.index.to_string(),
+            |value| format!("{}: {value}", cell.index),
+        );
+        writeln!(
+            out,
+            r#"<path d="{path}" fill="{}"><title>{title}</title></path>"#,
+            colors.color(cell.value),
+        )
+        .context("write cell")?;
+    }
+    writeln!(out, "</g>").context("write cells")?;
+
+    if options.labels {
+        write_labels(&mut out, cells, shapes, map, viewport)?;
+    }
+    if options.legend {
+        write_legend(&mut out, cells.len(), colors, options)?;
+    }
+
+    writeln!(out, "</svg>").context("write SVG footer")?;
+
+    Ok(())
+}
+
+/// Returns the SVG path data of a cell shape.
+fn path(shape: &MultiPolygon, viewport: &Viewport) -> String {
+    shape
+        .iter()
+        .map(|polygon| {
+            let points = polygon
+                .exterior()
+                .coords()
+                .map(|&coord| {
+                    let Coord { x, y } = viewport.pixel(coord);
+                    format!("{x:.2} {y:.2}")
+                })
+                .collect::<Vec<_>>();
+            format!("M{}Z", points.join("L"))
+        })
+        .collect::<Vec<_>>()
+        .concat()
+}
+
+/// Write the cell indexes, centered on the cells.
+///
+/// The font size is adjusted to the size of each cell.
+fn write_labels(
+    out: &mut impl Write,
+    cells: &[Cell],
+    shapes: &[MultiPolygon],
+    map: Map,
+    viewport: &Viewport,
+) -> AnyResult<()> {
+    writeln!(
+        out,
+        r#"<g font-family="sans-serif" text-anchor="middle" dominant-baseline="middle" fill="black">"#
+    )
+    .context("write labels")?;
+    for (cell, shape) in cells.iter().zip(shapes) {
+        let ll = LatLng::from(cell.index);
+        let center = Coord {
+            x: ll.lng(),
+            y: ll.lat(),
+        };
+        let Coord { x, y } = viewport.pixel(map.project(center));
+        // An index is 15 characters long, about 0.6em each.
+        let width = shape.bounding_rect().map_or(0., geo_types::Rect::width);
+        let size = width * viewport.scale() / 9.;
+        writeln!(
+            out,
+            r#"<text x="{x:.2}" y="{y:.2}" font-size="{size:.2}">{}</text>"#,
+            cell.index
+        )
+        .context("write label")?;
+    }
+    writeln!(out, "</g>").context("write labels")?;
+
+    Ok(())
+}
+
+/// Write the legend in the bottom-right corner.
+///
+/// The legend shows the color ramp with the range of values, or the number of
+/// cells if the cells have no value.
+fn write_legend(
+    out: &mut impl Write,
+    count: usize,
+    colors: &ColorScale,
+    options: Options,
+) -> AnyResult<()> {
+    let (bar_width, bar_height) = LEGEND_SIZE;
+    let x = f64::from(options.width) - bar_width - 20.;
+    let y = f64::from(options.height) - bar_height - 30.;
+
+    writeln!(
+        out,
+        r#"<g font-family="sans-serif" font-size="11" fill="black">"#
+    )
+    .context("write legend")?;
+    writeln!(
+        out,
+        r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="white" fill-opacity="0.8"/>"#,
+        x - 10.,
+        y - 10.,
+        bar_width + 20.,
+        bar_height + 36.
+    )
+    .context("write legend")?;
+    if let Some((min, max)) = colors.range() {
+        writeln!(out, r#"<linearGradient id="ramp">"#)
+            .context("write legend")?;
+        let stops = colors.ramp().stops();
+        for (stop, offset) in stops.iter().zip([0, 25, 50, 75, 100]) {
+            writeln!(out, r#"<stop offset="{offset}%" stop-color="{stop}"/>"#)
+                .context("write legend")?;
+        }
+        writeln!(out, "</linearGradient>").context("write legend")?;
+        writeln!(
+            out,
+            r#"<rect x="{x:.2}" y="{y:.2}" width="{bar_width}" height="{bar_height}" fill="url(#ramp)" stroke="{STROKE}" stroke-width="0.5"/>"#
+        )
+        .context("write legend")?;
+        let label_y = y + bar_height + 14.;
+        writeln!(
+            out,
+            r#"<text x="{x:.2}" y="{label_y:.2}">{min}</text><text x="{:.2}" y="{label_y:.2}" text-anchor="end">{max}</text>"#,
+            x + bar_width
+        )
+        .context("write legend")?;
+    } else {
+        writeln!(
+            out,
+            r#"<rect x="{x:.2}" y="{y:.2}" width="{bar_height}" height="{bar_height}" fill="{}" stroke="{STROKE}" stroke-width="0.5"/>"#,
+            colors.color(None)
+        )
+        .context("write legend")?;
+        writeln!(
+            out,
+            r#"<text x="{:.2}" y="{:.2}">{count} cells</text>"#,
+            x + bar_height + 6.,
+            y + bar_height - 2.
+        )
+        .context("write legend")?;
+    }
+    writeln!(out, "</g>").context("write legend")?;
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use anyhow::{Context, Result as AnyResult};
+use arrow_array::{
+    ArrayRef, BooleanArray, Float64Array, ListArray, RecordBatch, UInt8Array,
+    UInt64Array,
+    types::{UInt8Type, UInt64Type},
+};
+use arrow_ipc::writer::FileWriter;
+use parquet::arrow::ArrowWriter;
+use std::{io, path::Path, sync::Arc};
+
+/// A columnar table format.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    Parquet,
+    Arrow,
+}
+
+/// Write the record batch in the given format.
+///
+/// The table is written on `stdout` if no output file is given.
+pub fn write(
+    batch: &RecordBatch,
+    format: Format,
+    output: Option<&Path>,
+) -> AnyResult<()> {
+    match output {
+        Some(path) => write_to(crate::io::create(path)?, batch, format),
+        None => write_to(io::stdout(), batch, format),
+    }
+}
+
+/// Returns a column of cell indexes, as unsigned 64-bit integers.
+pub fn cells(cells: impl IntoIterator<Item = impl Into<u64>>) -> ArrayRef {
+    Arc::new(cells.into_iter().map(Into::into).collect::<UInt64Array>())
+}
+
+/// Returns a column of optional cell indexes.
+pub fn nullable_cells(
+    cells: impl IntoIterator<Item = Option<impl Into<u64>>>,
+) -> ArrayRef {
+    Arc::new(
+        cells
+            .into_iter()
+            .map(|cell| cell.map(Into::into))
+            .collect::<UInt64Array>(),
+    )
+}
+
+/// Returns a column of floating-point numbers.
+pub fn floats(values: impl IntoIterator<Item = f64>) -> ArrayRef {
+    Arc::new(values.into_iter().collect::<Float64Array>())
+}
+
+/// Returns a column of small integers.
+pub fn u8s(values: impl IntoIterator<Item = u8>) -> ArrayRef {
+    Arc::new(values.into_iter().collect::<UInt8Array>())
+}
+
+/// Returns a column of booleans.
+pub fn booleans(values: impl IntoIterator<Item = bool>) -> ArrayRef {
+    Arc::new(values.into_iter().map(Some).collect::<BooleanArray>())
+}
+
+/// Returns a column of lists of 64-bit identifiers (cells, vertexes, edges).
+pub fn u64_lists(lists: impl IntoIterator<Item = Vec<u64>>) -> ArrayRef {
+    Arc::new(ListArray::from_iter_primitive::<UInt64Type, _, _>(
+        lists
+            .into_iter()
+            .map(|list| Some(list.into_iter().map(Some))),
+    ))
+}
+
+/// Returns a column of lists of small integers.
+pub fn u8_lists(lists: impl IntoIterator<Item = Vec<u8>>) -> ArrayRef {
+    Arc::new(ListArray::from_iter_primitive::<UInt8Type, _, _>(
+        lists
+            .into_iter()
+            .map(|list| Some(list.into_iter().map(Some))),
+    ))
+}
+
+/// Write the record batch into `out`.
+fn write_to(
+    out: impl io::Write + Send,
+    batch: &RecordBatch,
+    format: Format,
+) -> AnyResult<()> {
+    match format {
+        Format::Parquet => {
+            let mut writer = ArrowWriter::try_new(out, batch.schema(), None)
+                .context("create Parquet writer")?;
+            writer.write(batch).context("write Parquet row group")?;
+            writer.close().context("finalize Parquet file")?;
+        }
+        Format::Arrow => {
+            let mut writer = FileWriter::try_new(out, &batch.schema())
+                .context("create Arrow writer")?;
+            writer.write(batch).context("write Arrow record batch")?;
+            writer.finish().context("finalize Arrow file")?;
+        }
+    }
+
+    Ok(())
+}
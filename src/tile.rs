@@ -0,0 +1,186 @@
+use anyhow::{Context, Error as AnyError, ensure};
+use geo_types::{Coord, Rect};
+use std::{
+    f64::consts::{FRAC_PI_4, PI},
+    fmt,
+    str::FromStr,
+};
+
+/// Latitude limit of the Web Mercator projection, in degrees.
+pub const MAX_LAT: f64 = 85.051_128_779_806_59;
+
+/// Highest supported zoom level.
+pub const MAX_ZOOM: u8 = 30;
+
+/// A Web Mercator (XYZ) tile.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tile {
+    pub zoom: u8,
+    pub x: u32,
+    pub y: u32,
+}
+
+impl Tile {
+    /// Returns the number of tiles along each axis at a zoom level.
+    pub const fn count(zoom: u8) -> u32 {
+        1 << zoom
+    }
+
+    /// Returns the tile containing a longitude/latitude coordinate.
+    ///
+    /// Latitudes beyond the projection's limits fall in the first or last
+    /// row.
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "tile numbers are clamped to the zoom level's range"
+    )]
+    pub fn containing(coord: Coord, zoom: u8) -> Self {
+        let count = f64::from(Self::count(zoom));
+        let coord = mercator(coord);
+        let number = |value: f64| (value * count).floor().clamp(0., count - 1.);
+
+        Self {
+            zoom,
+            x: number(coord.x) as u32,
+            y: number(coord.y) as u32,
+        }
+    }
+
+    /// Returns the bounds of the tile, in longitude/latitude.
+    ///
+    /// Tiles never go beyond the projection's latitude limits.
+    pub fn bounds(self) -> Rect {
+        let count = f64::from(Self::count(self.zoom));
+        let lng = |x: f64| (x / count).mul_add(360., -180.);
+        let lat = |y: f64| {
+            (PI * 2.0_f64.mul_add(-y / count, 1.))
+                .sinh()
+                .atan()
+                .to_degrees()
+        };
+        let (x, y) = (f64::from(self.x), f64::from(self.y));
+
+        Rect::new(
+            Coord {
+                x: lng(x),
+                y: lat(y + 1.),
+            },
+            Coord {
+                x: lng(x + 1.),
+                y: lat(y),
+            },
+        )
+    }
+
+    /// Returns the bounds of the tile, with the first and last rows extended
+    /// to the poles.
+    pub fn extended_bounds(self) -> Rect {
+        let bounds = self.bounds();
+        let last = Self::count(self.zoom) - 1;
+
+        Rect::new(
+            Coord {
+                x: bounds.min().x,
+                y: if self.y == last { -90. } else { bounds.min().y },
+            },
+            Coord {
+                x: bounds.max().x,
+                y: if self.y == 0 { 90. } else { bounds.max().y },
+            },
+        )
+    }
+
+    /// Returns the descendants of the tile at a finer zoom level, or the tile
+    /// itself if the zoom level isn't finer.
+    pub fn descendants(self, zoom: u8) -> impl Iterator<Item = Self> {
+        let shift = zoom.saturating_sub(self.zoom);
+        let (x, y) = (self.x << shift, self.y << shift);
+        (0..1 << shift).flat_map(move |dx| {
+            (0..1 << shift).map(move |dy| Self {
+                zoom: self.zoom + shift,
+                x: x + dx,
+                y: y + dy,
+            })
+        })
+    }
+
+    /// Returns the quadkey of the tile.
+    ///
+    /// The quadkey of the tile at zoom 0 is empty.
+    pub fn quadkey(self) -> String {
+        (1..=self.zoom)
+            .rev()
+            .map(|level| {
+                let mask = 1 << (level - 1);
+                let digit = u8::from(self.x & mask != 0)
+                    + 2 * u8::from(self.y & mask != 0);
+                char::from(b'0' + digit)
+            })
+            .collect()
+    }
+}
+
+impl FromStr for Tile {
+    type Err = AnyError;
+
+    /// Parse a tile from its `z/x/y` coordinates or its quadkey.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // The empty quadkey would be the whole world: reject it, as it's more
+        // likely a blank line than a deliberate tile 0/0/0.
+        ensure!(!s.is_empty(), "empty tile");
+        let tile = if s.contains('/') {
+            let mut parts = s.splitn(3, '/');
+            let mut part =
+                |name| parts.next().with_context(|| format!("missing {name}"));
+            let (zoom, x, y) = (part("zoom")?, part("x")?, part("y")?);
+            Self {
+                zoom: zoom.parse().context("invalid zoom")?,
+                x: x.parse().context("invalid x")?,
+                y: y.parse().context("invalid y")?,
+            }
+        } else {
+            let zoom = u8::try_from(s.len()).context("quadkey too long")?;
+            ensure!(zoom <= MAX_ZOOM, "quadkey too long");
+            s.chars()
+                .try_fold(Self { zoom, x: 0, y: 0 }, |tile, digit| {
+                    let digit = digit.to_digit(4).with_context(|| {
+                        format!("invalid quadkey digit {digit}")
+                    })?;
+                    Ok::<_, AnyError>(Self {
+                        zoom,
+                        x: (tile.x << 1) | (digit & 1),
+                        y: (tile.y << 1) | (digit >> 1),
+                    })
+                })?
+        };
+        ensure!(tile.zoom <= MAX_ZOOM, "zoom greater than {MAX_ZOOM}");
+        let count = Self::count(tile.zoom);
+        ensure!(
+            tile.x < count && tile.y < count,
+            "tile out of range for zoom {}",
+            tile.zoom
+        );
+
+        Ok(tile)
+    }
+}
+
+impl fmt::Display for Tile {
+    /// Format the tile as `z/x/y`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}/{}", self.zoom, self.x, self.y)
+    }
+}
+
+/// Project a longitude/latitude coordinate on the Web Mercator square.
+///
+/// The square goes from (0, 0) in the north-west corner to (1, 1) in the
+/// south-east one. Latitudes are clamped to the projection's limits.
+pub fn mercator(coord: Coord) -> Coord {
+    let lat = coord.y.clamp(-MAX_LAT, MAX_LAT).to_radians();
+    Coord {
+        x: (coord.x + 180.) / 360.,
+        y: 0.5 - (lat / 2. + FRAC_PI_4).tan().ln() / (2. * PI),
+    }
+}
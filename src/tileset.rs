@@ -0,0 +1,105 @@
+use crate::tile::Tile;
+use anyhow::{Context, Result as AnyResult};
+use flate2::{Compression, write::GzEncoder};
+use geo_types::Rect;
+use serde_json::json;
+use std::{collections::BTreeMap, fs, io::Write, path::Path};
+
+/// A tileset format.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    Directory,
+    Mbtiles,
+    Pmtiles,
+}
+
+/// Write the tileset in the given format.
+///
+/// Existing tiles or archives are overwritten.
+pub fn write(tileset: &Tileset, format: Format, path: &Path) -> AnyResult<()> {
+    match format {
+        Format::Directory => write_directory(tileset, path),
+        Format::Mbtiles => crate::mbtiles::write(path, tileset),
+        Format::Pmtiles => crate::pmtiles::write(path, tileset),
+    }
+}
+
+/// A set of vector tiles, made of a single layer.
+pub struct Tileset {
+    /// Layer name.
+    pub layer: String,
+    /// Layer attributes, with their type (`String` or `Number`).
+    pub fields: Vec<(&'static str, &'static str)>,
+    /// Minimum zoom level.
+    pub min_zoom: u8,
+    /// Maximum zoom level.
+    pub max_zoom: u8,
+    /// Extent of the features, in longitude/latitude.
+    pub bounds: Rect,
+    /// Encoded tiles, uncompressed.
+    pub tiles: BTreeMap<Tile, Vec<u8>>,
+}
+
+impl Tileset {
+    /// Returns the description of the layer, as expected in the `json` entry
+    /// of the `MBTiles` metadata.
+    pub fn vector_layers(&self) -> serde_json::Value {
+        let fields = self
+            .fields
+            .iter()
+            .map(|&(name, kind)| (name.to_owned(), json!(kind)))
+            .collect::<serde_json::Map<_, _>>();
+
+        json!([{
+            "id": self.layer,
+            "fields": fields,
+            "minzoom": self.min_zoom,
+            "maxzoom": self.max_zoom,
+        }])
+    }
+
+    /// Returns the center of the tileset, at its minimum zoom level.
+    pub fn center(&self) -> (f64, f64, u8) {
+        let center = self.bounds.center();
+        (center.x, center.y, self.min_zoom)
+    }
+}
+
+/// Compress data with gzip.
+pub fn gzip(data: &[u8]) -> AnyResult<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data).context("compress data")?;
+    encoder.finish().context("compress data")
+}
+
+/// Write the tiles as `{z}/{x}/{y}.mvt` files, along with a `metadata.json`
+/// file describing the tileset.
+fn write_directory(tileset: &Tileset, path: &Path) -> AnyResult<()> {
+    for (tile, data) in &tileset.tiles {
+        let dir = path.join(tile.zoom.to_string()).join(tile.x.to_string());
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("create {}", dir.display()))?;
+        let file = dir.join(format!("{}.mvt", tile.y));
+        fs::write(&file, data)
+            .with_context(|| format!("write {}", file.display()))?;
+    }
+
+    let (lng, lat, zoom) = tileset.center();
+    let metadata = json!({
+        "name": tileset.layer,
+        "format": "pbf",
+        "minzoom": tileset.min_zoom,
+        "maxzoom": tileset.max_zoom,
+        "bounds": [
+            tileset.bounds.min().x,
+            tileset.bounds.min().y,
+            tileset.bounds.max().x,
+            tileset.bounds.max().y,
+        ],
+        "center": [lng, lat, zoom],
+        "vector_layers": tileset.vector_layers(),
+    });
+    let file = path.join("metadata.json");
+    fs::write(&file, metadata.to_string())
+        .with_context(|| format!("write {}", file.display()))
+}
@@ -1,4 +1,4 @@
-use anyhow::Result as AnyResult;
+use anyhow::{Result as AnyResult, ensure};
 use either::Either;
 use h3o::CellIndex;
 
@@ -13,3 +13,72 @@ pub fn get_cell_indexes(
         |index| Either::Right(std::iter::once(Ok(index))),
     )
 }
+
+/// Get cell indexes and their extra fields, either from a CLI argument or
+/// `stdin`.
+///
+/// First try the CLI arg, and if not set then read from `stdin`.
+pub fn get_cell_records(
+    arg: Option<CellIndex>,
+) -> impl Iterator<Item = AnyResult<(CellIndex, Vec<String>)>> {
+    arg.map_or_else(
+        || Either::Left(crate::io::read_cell_records()),
+        |index| Either::Right(std::iter::once(Ok((index, Vec::new())))),
+    )
+}
+
+/// Get geohashes, either from a CLI argument or `stdin`.
+///
+/// First try the CLI arg, and if not set then read from `stdin`.
+pub fn get_geohashes(
+    arg: Option<crate::geohash::Geohash>,
+) -> impl Iterator<Item = AnyResult<crate::geohash::Geohash>> {
+    arg.map_or_else(
+        || Either::Left(crate::io::read_geohashes()),
+        |geohash| Either::Right(std::iter::once(Ok(geohash))),
+    )
+}
+
+/// Get Web Mercator tiles, either from a CLI argument or `stdin`.
+///
+/// First try the CLI arg, and if not set then read from `stdin`.
+pub fn get_tiles(
+    arg: Option<crate::tile::Tile>,
+) -> impl Iterator<Item = AnyResult<crate::tile::Tile>> {
+    arg.map_or_else(
+        || Either::Left(crate::io::read_tiles()),
+        |tile| Either::Right(std::iter::once(Ok(tile))),
+    )
+}
+
+/// Maximum number of cells `uncompact_to_finest` may expand its input into.
+const UNCOMPACT_BUDGET: u64 = 10_000_000;
+
+/// Normalize cells to a single resolution.
+///
+/// Mixed-resolution cells are uncompacted to the finest resolution, and the
+/// resulting duplicates (from overlapping input cells) are removed. Fails if
+/// that would produce more than ten million cells.
+pub fn uncompact_to_finest(
+    mut cells: Vec<CellIndex>,
+) -> AnyResult<Vec<CellIndex>> {
+    let resolutions = cells.iter().map(|cell| cell.resolution());
+    if let (Some(coarsest), Some(finest)) =
+        (resolutions.clone().min(), resolutions.max())
+        && coarsest != finest
+    {
+        let count = cells.iter().fold(0_u64, |count, cell| {
+            count.saturating_add(cell.children_count(finest))
+        });
+        ensure!(
+            count <= UNCOMPACT_BUDGET,
+            "uncompacting resolutions {coarsest} to {finest} yields {count} \
+             cells, more than {UNCOMPACT_BUDGET}"
+        );
+        cells = CellIndex::uncompact(cells, finest).collect();
+        cells.sort_unstable();
+        cells.dedup();
+    }
+
+    Ok(cells)
+}
@@ -1,4 +1,18 @@
-use std::{env, path::PathBuf};
+use arrow_array::{RecordBatch, UInt8Array, UInt32Array, UInt64Array};
+use arrow_ipc::reader::FileReader;
+use flatgeobuf::{FallibleStreamingIterator, FeatureProperties, FgbReader};
+use geo_types::{Geometry, LineString};
+use geozero::{ToGeo, wkb::GpkgWkb};
+use h3o::{CellIndex, Resolution};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use rusqlite::Connection;
+use std::{
+    env,
+    fs::{self, File},
+    io::{Cursor, Write},
+    path::PathBuf,
+    process::{Command, Stdio},
+};
 
 const CRATE_PATH: &str = env!("CARGO_MANIFEST_DIR");
 
@@ -36,6 +50,7 @@ fn lit() {
     lit::run::tests(lit::event_handler::Default::default(), |config| {
         config.add_search_path(format!("{CRATE_PATH}/tests/lit"));
         config.add_extension("geojson");
+        config.add_extension("gpx");
         config.add_extension("kml");
         config.add_extension("txt");
         config
@@ -44,3 +59,217 @@ fn lit() {
     })
     .expect("lit tests failed");
 }
+
+/// Run the CLI with the given arguments and input, and returns its output.
+fn run(args: &[&str], input: &str) -> Vec<u8> {
+    let mut child = Command::new(cargo_bin("h3o-cli"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("spawn h3o-cli");
+    child
+        .stdin
+        .take()
+        .expect("stdin")
+        .write_all(input.as_bytes())
+        .expect("write stdin");
+    let output = child.wait_with_output().expect("run h3o-cli");
+    assert!(output.status.success(), "h3o-cli {args:?} failed");
+
+    output.stdout
+}
+
+/// Returns a path for a temporary output file.
+fn temp_path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("h3o-cli-{}-{name}", std::process::id()))
+}
+
+/// Asserts that the ring is the closed boundary of the cell.
+fn assert_boundary(ring: &LineString, index: CellIndex) {
+    let boundary = index.boundary();
+    assert_eq!(ring.0.len(), boundary.len() + 1, "closed ring");
+    assert_eq!(ring.0.first(), ring.0.last(), "closed ring");
+    for coord in &ring.0 {
+        assert!(
+            boundary.iter().any(|vertex| {
+                (vertex.lng() - coord.x).abs() < 1e-9
+                    && (vertex.lat() - coord.y).abs() < 1e-9
+            }),
+            "{coord:?} isn't a vertex of {index}"
+        );
+    }
+}
+
+#[test]
+fn flatgeobuf_roundtrip() {
+    let index = "8a1fb4662d47fff".parse::<CellIndex>().expect("cell index");
+    let output = run(
+        &["cellToBoundary", "-f", "flatgeobuf", "--columns", "zone"],
+        "8a1fb4662d47fff zoneA\n",
+    );
+
+    let mut features = FgbReader::open(Cursor::new(output))
+        .expect("FlatGeobuf header")
+        .select_all()
+        .expect("FlatGeobuf features");
+    let feature = features.next().expect("feature").expect("one feature");
+    assert_eq!(
+        feature.property::<String>("cell").expect("cell"),
+        "8a1fb4662d47fff"
+    );
+    assert_eq!(
+        feature.property::<i64>("resolution").expect("resolution"),
+        10
+    );
+    assert_eq!(feature.property::<String>("zone").expect("zone"), "zoneA");
+    // geozero reads polygons of untyped layers back as multipolygons.
+    let Ok(Geometry::MultiPolygon(polygons)) = feature.to_geo() else {
+        panic!("multipolygon geometry");
+    };
+    assert_eq!(polygons.0.len(), 1, "one polygon");
+    assert_boundary(polygons.0[0].exterior(), index);
+    assert!(features.next().expect("feature").is_none(), "one feature");
+}
+
+#[test]
+fn gpkg_roundtrip() {
+    let index = "8a1fb4662d47fff".parse::<CellIndex>().expect("cell index");
+    let path = temp_path("roundtrip.gpkg");
+    let path_arg = path.to_str().expect("valid unicode path");
+    run(
+        &[
+            "cellToPolygon",
+            "-i",
+            "8a1fb4662d47fff",
+            "-f",
+            "gpkg",
+            "-o",
+            path_arg,
+        ],
+        "",
+    );
+
+    let conn = Connection::open(&path).expect("open GeoPackage");
+    let (resolution, count, geom) = conn
+        .query_row("SELECT resolution, count, geom FROM polygons", [], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, Vec<u8>>(2)?,
+            ))
+        })
+        .expect("read feature");
+    drop(conn);
+    fs::remove_file(&path).expect("remove GeoPackage");
+
+    assert_eq!((resolution, count), (10, 1));
+    let Ok(Geometry::MultiPolygon(polygons)) = GpkgWkb(geom).to_geo() else {
+        panic!("multipolygon geometry");
+    };
+    assert_eq!(polygons.0.len(), 1, "one polygon");
+    assert_boundary(polygons.0[0].exterior(), index);
+}
+
+#[test]
+fn parquet_roundtrip() {
+    let index = "8a1fb46622dffff".parse::<CellIndex>().expect("cell index");
+    let path = temp_path("roundtrip.parquet");
+    let path_arg = path.to_str().expect("valid unicode path");
+    run(
+        &[
+            "cellInfo",
+            "-i",
+            "8a1fb46622dffff",
+            "-f",
+            "parquet",
+            "-o",
+            path_arg,
+        ],
+        "",
+    );
+
+    let file = File::open(&path).expect("open Parquet file");
+    let batches = ParquetRecordBatchReaderBuilder::try_new(file)
+        .expect("Parquet metadata")
+        .build()
+        .expect("Parquet reader")
+        .collect::<Result<Vec<_>, _>>()
+        .expect("read Parquet batches");
+    fs::remove_file(&path).expect("remove Parquet file");
+
+    let [ref batch] = *batches else {
+        panic!("one record batch");
+    };
+    let schema = batch.schema();
+    let names = schema
+        .fields()
+        .iter()
+        .map(|field| field.name().as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names.first(), Some(&"index"));
+    assert_eq!(names.last(), Some(&"is_class3"));
+    assert_eq!(batch.num_rows(), 1);
+    assert_eq!(u64_column(batch, "index").value(0), u64::from(index));
+    assert_eq!(
+        u64_column(batch, "parent").value(0),
+        u64::from(index.parent(Resolution::Nine).expect("parent"))
+    );
+    let resolution = batch
+        .column_by_name("resolution")
+        .and_then(|column| column.as_any().downcast_ref::<UInt8Array>())
+        .expect("resolution column");
+    assert_eq!(resolution.value(0), 10);
+}
+
+#[test]
+fn arrow_roundtrip() {
+    let origin = "8a1fb46622dffff".parse::<CellIndex>().expect("cell index");
+    let output = run(
+        &[
+            "gridDisk",
+            "-o",
+            "8a1fb46622dffff",
+            "-r",
+            "1",
+            "-d",
+            "-f",
+            "arrow",
+        ],
+        "",
+    );
+
+    let batches = FileReader::try_new(Cursor::new(output), None)
+        .expect("Arrow header")
+        .collect::<Result<Vec<_>, _>>()
+        .expect("read Arrow batches");
+    let [ref batch] = *batches else {
+        panic!("one record batch");
+    };
+    let origins = u64_column(batch, "origin");
+    let cells = u64_column(batch, "cell");
+    let distances = batch
+        .column_by_name("distance")
+        .and_then(|column| column.as_any().downcast_ref::<UInt32Array>())
+        .expect("distance column");
+
+    let mut rows = (0..batch.num_rows())
+        .map(|i| (origins.value(i), cells.value(i), distances.value(i)))
+        .collect::<Vec<_>>();
+    rows.sort_unstable();
+    let mut expected = origin
+        .grid_disk_distances::<Vec<_>>(1)
+        .into_iter()
+        .map(|(cell, distance)| (u64::from(origin), u64::from(cell), distance))
+        .collect::<Vec<_>>();
+    expected.sort_unstable();
+    assert_eq!(rows, expected);
+}
+
+/// Returns the named column of cell indexes.
+fn u64_column<'a>(batch: &'a RecordBatch, name: &str) -> &'a UInt64Array {
+    batch
+        .column_by_name(name)
+        .and_then(|column| column.as_any().downcast_ref::<UInt64Array>())
+        .unwrap_or_else(|| panic!("{name} column"))
+}